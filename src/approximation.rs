@@ -0,0 +1,319 @@
+use crate::curve::{BSplineCurve, BezierCurve, Curve};
+use crate::interpolation::{self, Parametrization};
+use crate::{bezier, bspline, linalg, vector};
+
+pub struct Fit {
+    pub pin_ends: bool,
+    pub weights: Option<Vec<f64>>,
+    pub parametrization: Parametrization,
+}
+
+impl Default for Fit {
+    fn default() -> Self {
+        Self {
+            pin_ends: true,
+            weights: None,
+            parametrization: Parametrization::ChordLength,
+        }
+    }
+}
+
+// Consecutive copies of a point share its chord-length or centripetal parameter, so they are merged
+// into one point weighing as much as all of them, which leaves the least squares unchanged.
+fn merge_repeated(points: &[Vec<f64>], fit: &Fit) -> (Vec<Vec<f64>>, Fit) {
+    let mut merged: Vec<Vec<f64>> = Vec::with_capacity(points.len());
+    let mut weights: Vec<f64> = Vec::with_capacity(points.len());
+    for (k, point) in points.iter().enumerate() {
+        let weight = fit.weights.as_ref().map_or(1.0, |weights| weights[k]);
+        if merged.last() == Some(point) {
+            let last = weights.len() - 1;
+            weights[last] += weight;
+        } else {
+            merged.push(point.clone());
+            weights.push(weight);
+        }
+    }
+
+    let fit = Fit {
+        pin_ends: fit.pin_ends,
+        weights: Some(weights),
+        parametrization: fit.parametrization,
+    };
+    (merged, fit)
+}
+
+// Weighted least squares for the control points given the basis functions `basis(u, i)`. Pinned
+// end points are fixed to the first and last data points and the rest is fitted around them.
+fn least_squares(
+    points: &[Vec<f64>],
+    parameters: &[f64],
+    count: usize,
+    fit: &Fit,
+    basis: impl Fn(f64, usize) -> f64,
+) -> Option<Vec<Vec<f64>>> {
+    let dimension = points[0].len();
+    let weight = |k: usize| fit.weights.as_ref().map_or(1.0, |weights| weights[k]);
+    let (unknowns, data) = if fit.pin_ends {
+        (1..count - 1, 1..points.len() - 1)
+    } else {
+        (0..count, 0..points.len())
+    };
+    let size = unknowns.len();
+
+    let mut matrix = vec![vec![0.0; size]; size];
+    let mut rhs = vec![vec![0.0; dimension]; size];
+    for k in data {
+        let row: Vec<f64> = (0..count).map(|i| basis(parameters[k], i)).collect();
+        let mut residual = points[k].clone();
+        if fit.pin_ends {
+            residual = vector::sub(&residual, &vector::scale(&points[0], row[0]));
+            residual = vector::sub(
+                &residual,
+                &vector::scale(&points[points.len() - 1], row[count - 1]),
+            );
+        }
+
+        for (a, i) in unknowns.clone().enumerate() {
+            for (b, j) in unknowns.clone().enumerate() {
+                matrix[a][b] += weight(k) * row[i] * row[j];
+            }
+            rhs[a] = vector::add(&rhs[a], &vector::scale(&residual, weight(k) * row[i]));
+        }
+    }
+
+    let solution = linalg::solve(matrix, rhs)?;
+    if !fit.pin_ends {
+        return Some(solution);
+    }
+
+    let mut control_points = Vec::with_capacity(count);
+    control_points.push(points[0].clone());
+    control_points.extend(solution);
+    control_points.push(points[points.len() - 1].clone());
+    Some(control_points)
+}
+
+// Knots spread so that every span holds data (Piegl & Tiller eq. 9.69).
+fn fitting_knots(parameters: &[f64], degree: usize, count: usize) -> Vec<f64> {
+    let p = degree - 1;
+    let d = parameters.len() as f64 / (count - p) as f64;
+
+    let mut knots = vec![0.0; degree];
+    for j in 1..count - p {
+        let i = (j as f64 * d) as usize;
+        let alpha = j as f64 * d - i as f64;
+        knots.push((1.0 - alpha) * parameters[i - 1] + alpha * parameters[i]);
+    }
+    knots.extend(std::iter::repeat_n(1.0, degree));
+    knots
+}
+
+// The basis functions vanish at the very end of the domain, where the clamped curve is its last
+// control point.
+fn clamped_basis(u: f64, i: usize, degree: usize, knots: &[f64]) -> f64 {
+    let count = knots.len() - degree;
+    if u >= knots[count] {
+        return if i == count - 1 { 1.0 } else { 0.0 };
+    }
+    bspline::basis_function(u, i, degree, knots)
+}
+
+fn fit_with_knots(
+    points: &[Vec<f64>],
+    parameters: &[f64],
+    degree: usize,
+    knots: &[f64],
+    fit: &Fit,
+) -> Option<BSplineCurve> {
+    let count = knots.len() - degree;
+    let control_points = least_squares(points, parameters, count, fit, |u, i| {
+        clamped_basis(u, i, degree, knots)
+    })?;
+    Some(BSplineCurve::with_knots(
+        control_points,
+        degree,
+        knots.to_vec(),
+    ))
+}
+
+// `count` control points fitted to the data, of which there must be at least as many distinct
+// points.
+pub fn fit_bspline(points: &[Vec<f64>], degree: usize, count: usize, fit: &Fit) -> BSplineCurve {
+    let (points, fit) = merge_repeated(points, fit);
+    assert!(
+        degree <= count && count <= points.len(),
+        "{} distinct points cannot be fitted by {} control points of order {}",
+        points.len(),
+        count,
+        degree
+    );
+    let parameters = interpolation::parameters(&points, fit.parametrization);
+    let knots = fitting_knots(&parameters, degree, count);
+
+    fit_with_knots(&points, &parameters, degree, &knots, &fit)
+        .expect("every knot span holds a distinct parameter")
+}
+
+// Like `fit_bspline`, where pinned ends need at least the two end points as control points.
+pub fn fit_bezier(points: &[Vec<f64>], count: usize, fit: &Fit) -> BezierCurve {
+    let (points, fit) = merge_repeated(points, fit);
+    assert!(
+        (!fit.pin_ends || count >= 2) && count >= 1 && count <= points.len(),
+        "{} distinct points cannot be fitted by {} control points{}",
+        points.len(),
+        count,
+        if fit.pin_ends {
+            " with pinned ends"
+        } else {
+            ""
+        }
+    );
+    let parameters = interpolation::parameters(&points, fit.parametrization);
+    let n = count as u128 - 1;
+
+    let control_points = least_squares(&points, &parameters, count, &fit, |u, i| {
+        bezier::dk_bezier(u, i as i128, n, 0)
+    })
+    .expect("there are at least as many distinct parameters as control points");
+    BezierCurve::new(control_points)
+}
+
+// Starts from a single Bézier span and keeps halving the span with the worst data point until
+// every point is within `tolerance`, or no more knots fit.
+pub fn fit_bspline_to_tolerance(
+    points: &[Vec<f64>],
+    degree: usize,
+    tolerance: f64,
+    fit: &Fit,
+) -> BSplineCurve {
+    let (points, fit) = merge_repeated(points, fit);
+    assert!(
+        degree <= points.len(),
+        "{} distinct points cannot be fitted by a curve of order {}",
+        points.len(),
+        degree
+    );
+    let parameters = interpolation::parameters(&points, fit.parametrization);
+    let mut knots = fitting_knots(&parameters, degree, degree);
+    let mut curve = fit_with_knots(&points, &parameters, degree, &knots, &fit)
+        .expect("a single span holds every distinct parameter");
+
+    while curve.control_points.len() < points.len() {
+        let (worst, error) = points
+            .iter()
+            .zip(&parameters)
+            .map(|(point, &u)| (u, vector::distance(point, &curve.point(u))))
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .unwrap();
+        if error <= tolerance {
+            break;
+        }
+
+        let span = knots.iter().rposition(|&t| t <= worst).unwrap();
+        let span = span.min(knots.len() - degree - 1);
+        let middle = 0.5 * (knots[span] + knots[span + 1]);
+        let mut refined_knots = knots.clone();
+        refined_knots.insert(span + 1, middle);
+
+        match fit_with_knots(&points, &parameters, degree, &refined_knots, &fit) {
+            Some(refined) => {
+                knots = refined_knots;
+                curve = refined;
+            }
+            None => break,
+        }
+    }
+
+    curve
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Samples of the cubic y = x³ - x, which a single cubic span reproduces exactly.
+    fn points() -> Vec<Vec<f64>> {
+        (0..=10)
+            .map(|k| {
+                let x = -1.0 + 0.2 * k as f64;
+                vec![x, x * x * x - x, 0.0]
+            })
+            .collect()
+    }
+
+    fn with_repeats(points: &[Vec<f64>]) -> Vec<Vec<f64>> {
+        points
+            .iter()
+            .enumerate()
+            .flat_map(|(k, point)| std::iter::repeat_n(point.clone(), 1 + k % 3))
+            .collect()
+    }
+
+    #[test]
+    fn repeated_points_weigh_as_their_copies() {
+        let points = points();
+        let repeated = with_repeats(&points);
+        let weights = points
+            .iter()
+            .enumerate()
+            .map(|(k, _)| (1 + k % 3) as f64)
+            .collect();
+        let weighted = Fit {
+            weights: Some(weights),
+            ..Fit::default()
+        };
+
+        let merged = fit_bspline(&repeated, 4, 6, &Fit::default());
+        let expected = fit_bspline(&points, 4, 6, &weighted);
+        for (a, b) in merged.control_points.iter().zip(&expected.control_points) {
+            assert!(vector::distance(a, b) < 1e-9);
+        }
+    }
+
+    #[test]
+    fn fits_repeated_points_to_tolerance() {
+        let points = points();
+        let curve = fit_bspline_to_tolerance(&with_repeats(&points), 4, 1e-6, &Fit::default());
+        let parameters = interpolation::parameters(&points, Parametrization::ChordLength);
+        for (point, u) in points.iter().zip(parameters) {
+            assert!(vector::distance(point, &curve.point(u)) <= 1e-6);
+        }
+
+        let bezier = fit_bezier(&with_repeats(&points), 4, &Fit::default());
+        assert_eq!(bezier.control_points[0], points[0]);
+        assert_eq!(bezier.control_points[3], points[points.len() - 1]);
+    }
+
+    #[test]
+    fn refines_the_knots_until_the_fit_is_within_tolerance() {
+        // No single cubic span follows a full period of the sine.
+        let points: Vec<Vec<f64>> = (0..=60)
+            .map(|k| {
+                let x = 2.0 * std::f64::consts::PI * k as f64 / 60.0;
+                vec![x, x.sin(), 0.0]
+            })
+            .collect();
+        let (degree, tolerance) = (4, 1e-4);
+        let curve = fit_bspline_to_tolerance(&points, degree, tolerance, &Fit::default());
+        assert!(
+            curve.knots.len() > 2 * degree,
+            "the fit has no interior knots"
+        );
+        assert!(
+            curve.control_points.len() < points.len(),
+            "the fit interpolates every point"
+        );
+
+        let parameters = interpolation::parameters(&points, Fit::default().parametrization);
+        for (point, u) in points.iter().zip(parameters) {
+            let error = vector::distance(point, &curve.point(u));
+            assert!(error <= tolerance, "the fit is {error} away at {u}");
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "with pinned ends")]
+    fn pinned_bezier_needs_two_control_points() {
+        fit_bezier(&points(), 1, &Fit::default());
+    }
+}
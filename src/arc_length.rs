@@ -0,0 +1,245 @@
+use crate::curve::Curve;
+use crate::vector;
+
+pub(crate) const GAUSS_NODES: [f64; 5] = [
+    -0.906_179_845_938_664,
+    -0.538_469_310_105_683_1,
+    0.0,
+    0.538_469_310_105_683_1,
+    0.906_179_845_938_664,
+];
+pub(crate) const GAUSS_WEIGHTS: [f64; 5] = [
+    0.236_926_885_056_189_1,
+    0.478_628_670_499_366_5,
+    0.568_888_888_888_888_9,
+    0.478_628_670_499_366_5,
+    0.236_926_885_056_189_1,
+];
+const TOLERANCE: f64 = 1e-10;
+const MAX_DEPTH: usize = 30;
+const TABLE_SIZE: usize = 1024;
+
+fn speed(curve: &impl Curve, u: f64) -> f64 {
+    vector::norm(&curve.derivative(u, 1))
+}
+
+fn gauss_legendre(curve: &impl Curve, start: f64, end: f64) -> f64 {
+    let half = 0.5 * (end - start);
+    let middle = 0.5 * (end + start);
+
+    GAUSS_NODES
+        .iter()
+        .zip(GAUSS_WEIGHTS)
+        .map(|(node, weight)| weight * speed(curve, middle + half * node))
+        .sum::<f64>()
+        * half
+}
+
+fn adaptive_gauss_legendre(
+    curve: &impl Curve,
+    start: f64,
+    end: f64,
+    whole: f64,
+    tolerance: f64,
+    depth: usize,
+) -> f64 {
+    let middle = 0.5 * (start + end);
+    let left = gauss_legendre(curve, start, middle);
+    let right = gauss_legendre(curve, middle, end);
+
+    if depth >= MAX_DEPTH || (left + right - whole).abs() <= tolerance {
+        return left + right;
+    }
+
+    adaptive_gauss_legendre(curve, start, middle, left, 0.5 * tolerance, depth + 1)
+        + adaptive_gauss_legendre(curve, middle, end, right, 0.5 * tolerance, depth + 1)
+}
+
+pub fn arc_length_between(curve: &impl Curve, start: f64, end: f64) -> f64 {
+    if start == end {
+        return 0.0;
+    }
+    if end < start {
+        return -arc_length_between(curve, end, start);
+    }
+
+    let whole = gauss_legendre(curve, start, end);
+    adaptive_gauss_legendre(curve, start, end, whole, TOLERANCE, 0)
+}
+
+pub fn arc_length(curve: &impl Curve) -> f64 {
+    let (start, end) = curve.domain();
+    arc_length_between(curve, start, end)
+}
+
+// Solves `length_to(u) = length` with Newton steps from `u`, falling back to bisection whenever a
+// step leaves the bracket `lower..upper`.
+fn solve_parameter(
+    curve: &impl Curve,
+    length_to: impl Fn(f64) -> f64,
+    length: f64,
+    mut lower: f64,
+    mut upper: f64,
+    mut u: f64,
+) -> f64 {
+    for _ in 0..MAX_DEPTH {
+        let error = length_to(u) - length;
+        if error.abs() <= TOLERANCE {
+            break;
+        }
+        if error > 0.0 {
+            upper = u;
+        } else {
+            lower = u;
+        }
+
+        let derivative = speed(curve, u);
+        let next = u - error / derivative;
+        u = if derivative > 0.0 && lower < next && next < upper {
+            next
+        } else {
+            0.5 * (lower + upper)
+        };
+    }
+
+    u
+}
+
+pub fn parameter_at_length(curve: &impl Curve, length: f64) -> f64 {
+    let (start, end) = curve.domain();
+    if length <= 0.0 {
+        return start;
+    }
+    let total = arc_length(curve);
+    if length >= total {
+        return end;
+    }
+
+    let guess = start + (end - start) * length / total;
+    solve_parameter(
+        curve,
+        |u| arc_length_between(curve, start, u),
+        length,
+        start,
+        end,
+        guess,
+    )
+}
+
+pub fn uniform_parameters(curve: &impl Curve, samples: usize) -> Vec<f64> {
+    let (start, end) = curve.domain();
+
+    // Cumulative lengths over a fine parameter grid narrow every search down to one cell.
+    let mut parameters = Vec::with_capacity(TABLE_SIZE + 1);
+    let mut lengths = Vec::with_capacity(TABLE_SIZE + 1);
+    parameters.push(start);
+    lengths.push(0.0);
+    for j in 1..=TABLE_SIZE {
+        let u = start + (end - start) * j as f64 / TABLE_SIZE as f64;
+        let length = lengths[j - 1] + arc_length_between(curve, parameters[j - 1], u);
+        parameters.push(u);
+        lengths.push(length);
+    }
+    let total = lengths[TABLE_SIZE];
+
+    (0..samples)
+        .map(|j| {
+            if j == samples - 1 {
+                return end;
+            }
+            let length = total * j as f64 / (samples - 1) as f64;
+            let cell = lengths
+                .partition_point(|&l| l <= length)
+                .clamp(1, TABLE_SIZE);
+            let (lower, upper) = (parameters[cell - 1], parameters[cell]);
+            let (lower_length, upper_length) = (lengths[cell - 1], lengths[cell]);
+            let guess =
+                lower + (upper - lower) * (length - lower_length) / (upper_length - lower_length);
+
+            // Cells are small enough for a single Gauss-Legendre rule to be accurate.
+            solve_parameter(
+                curve,
+                |u| gauss_legendre(curve, lower, u),
+                length - lower_length,
+                lower,
+                upper,
+                guess,
+            )
+        })
+        .collect()
+}
+
+pub fn uniform_points(curve: &impl Curve, samples: usize) -> Vec<Vec<f64>> {
+    uniform_parameters(curve, samples)
+        .into_iter()
+        .map(|u| curve.point(u))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curve::{BezierCurve, NurbsCurve};
+    use std::f64::consts::{FRAC_1_SQRT_2, FRAC_PI_2};
+
+    // Unevenly spaced control points, so the line is not travelled at constant speed.
+    fn line() -> BezierCurve {
+        BezierCurve::new(vec![
+            vec![0.0, 0.0, 0.0],
+            vec![0.3, 0.4, 0.0],
+            vec![2.7, 3.6, 0.0],
+            vec![3.0, 4.0, 0.0],
+        ])
+    }
+
+    fn quarter_circle() -> NurbsCurve {
+        NurbsCurve::with_knots(
+            vec![
+                vec![1.0, 0.0, 0.0],
+                vec![1.0, 1.0, 0.0],
+                vec![0.0, 1.0, 0.0],
+            ],
+            vec![1.0, FRAC_1_SQRT_2, 1.0],
+            3,
+            vec![0.0, 0.0, 0.0, 1.0, 1.0, 1.0],
+        )
+    }
+
+    #[test]
+    fn lengths_of_a_line_and_an_arc() {
+        assert!((arc_length(&line()) - 5.0).abs() < 1e-9);
+        assert!((arc_length_between(&line(), 1.0, 0.0) + 5.0).abs() < 1e-9);
+        assert!((arc_length(&quarter_circle()) - FRAC_PI_2).abs() < 1e-9);
+    }
+
+    #[test]
+    fn parameters_at_lengths_round_trip() {
+        let circle = quarter_circle();
+        for length in [0.0, 0.1, 0.5, 1.2, FRAC_PI_2] {
+            let u = parameter_at_length(&circle, length);
+            assert!((arc_length_between(&circle, 0.0, u) - length).abs() < 1e-9);
+        }
+        assert_eq!(parameter_at_length(&circle, 10.0), 1.0);
+    }
+
+    #[test]
+    fn uniform_parameters_are_evenly_spaced() {
+        let line = line();
+        let points = uniform_points(&line, 11);
+        for (j, point) in points.iter().enumerate() {
+            let expected = [0.3 * j as f64, 0.4 * j as f64, 0.0];
+            assert!(vector::distance(point, &expected) < 1e-9);
+        }
+
+        // On the circle, equal lengths are equal angles.
+        let circle = quarter_circle();
+        let parameters = uniform_parameters(&circle, 7);
+        assert_eq!((parameters[0], parameters[6]), (0.0, 1.0));
+        for (j, &u) in parameters.iter().enumerate() {
+            let point = circle.point(u);
+            let angle = point[1].atan2(point[0]);
+            assert!((angle - FRAC_PI_2 * j as f64 / 6.0).abs() < 1e-9);
+            assert!((parameter_at_length(&circle, angle) - u).abs() < 1e-9);
+        }
+    }
+}
@@ -30,7 +30,7 @@ pub fn bezier(points: &[Vec<f64>], u: f64) -> Vec<f64> {
 }
 
 pub fn dk_bezier(u: f64, i: i128, n: u128, k: usize) -> f64 {
-    if i > n as i128 || i < 0 {
+    if i > n as i128 || i < 0 || k as u128 > n {
         return 0.0;
     }
 
@@ -59,3 +59,89 @@ pub fn derivative_bezier(points: &[Vec<f64>], u: f64, k: usize) -> Vec<f64> {
 
     p
 }
+
+pub fn reverse(points: &[Vec<f64>]) -> Vec<Vec<f64>> {
+    points.iter().rev().cloned().collect()
+}
+
+// de Casteljau subdivision: the control points of the pieces over [0, u] and [u, 1].
+pub fn split(points: &[Vec<f64>], u: f64) -> (Vec<Vec<f64>>, Vec<Vec<f64>>) {
+    let mut interpolated_points = points.to_vec();
+    let mut left = Vec::with_capacity(points.len());
+    let mut right = Vec::with_capacity(points.len());
+
+    left.push(interpolated_points[0].clone());
+    right.push(interpolated_points[interpolated_points.len() - 1].clone());
+    for i in (0..interpolated_points.len() - 1).rev() {
+        for j in 0..=i {
+            interpolated_points[j] = lerp(&interpolated_points[j], &interpolated_points[j + 1], u);
+        }
+        left.push(interpolated_points[0].clone());
+        right.push(interpolated_points[i].clone());
+    }
+    right.reverse();
+
+    (left, right)
+}
+
+// The same curve written with one more control point: Q_i = i/(n+1) P_{i-1} + (1 - i/(n+1)) P_i.
+pub fn elevate(points: &[Vec<f64>]) -> Vec<Vec<f64>> {
+    let n = points.len() - 1;
+    let mut elevated = Vec::with_capacity(points.len() + 1);
+
+    elevated.push(points[0].clone());
+    for i in 1..=n {
+        let alpha = i as f64 / (n + 1) as f64;
+        elevated.push(lerp(&points[i], &points[i - 1], alpha));
+    }
+    elevated.push(points[n].clone());
+
+    elevated
+}
+
+// Control points of the first derivative, a Bézier curve of one degree less.
+pub fn hodograph(points: &[Vec<f64>]) -> Vec<Vec<f64>> {
+    let n = (points.len() - 1) as f64;
+    points
+        .windows(2)
+        .map(|pair| {
+            pair[1]
+                .iter()
+                .zip(&pair[0])
+                .map(|(b, a)| n * (b - a))
+                .collect()
+        })
+        .collect()
+}
+
+fn find_roots(coefficients: &[f64], start: f64, end: f64, depth: usize, roots: &mut Vec<f64>) {
+    // By the convex hull property there is no root when every coefficient has the same sign.
+    if coefficients.iter().all(|&c| c > 0.0) || coefficients.iter().all(|&c| c < 0.0) {
+        return;
+    }
+
+    let middle = 0.5 * (start + end);
+    if depth == 52 || end - start <= 1e-13 {
+        if roots.last().is_none_or(|&root| middle - root > 1e-9) {
+            roots.push(middle);
+        }
+        return;
+    }
+
+    let points: Vec<Vec<f64>> = coefficients.iter().map(|&c| vec![c]).collect();
+    let (left, right) = split(&points, 0.5);
+    let left: Vec<f64> = left.into_iter().map(|p| p[0]).collect();
+    let right: Vec<f64> = right.into_iter().map(|p| p[0]).collect();
+    find_roots(&left, start, middle, depth + 1, roots);
+    find_roots(&right, middle, end, depth + 1, roots);
+}
+
+// Roots in [0, 1] of the polynomial with the given Bernstein coefficients, in increasing order. A
+// polynomial that vanishes everywhere is reported as having no roots.
+pub fn roots(coefficients: &[f64]) -> Vec<f64> {
+    let mut roots = Vec::new();
+    if coefficients.iter().any(|&c| c != 0.0) {
+        find_roots(coefficients, 0.0, 1.0, 0, &mut roots);
+    }
+    roots
+}
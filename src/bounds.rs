@@ -0,0 +1,350 @@
+use crate::curve::Shape;
+use crate::{bezier, bspline, vector};
+
+#[derive(Clone, Debug)]
+pub struct BoundingBox {
+    pub min: Vec<f64>,
+    pub max: Vec<f64>,
+}
+
+impl BoundingBox {
+    pub fn union(&self, other: &BoundingBox) -> BoundingBox {
+        BoundingBox {
+            min: self
+                .min
+                .iter()
+                .zip(&other.min)
+                .map(|(a, b)| a.min(*b))
+                .collect(),
+            max: self
+                .max
+                .iter()
+                .zip(&other.max)
+                .map(|(a, b)| a.max(*b))
+                .collect(),
+        }
+    }
+
+    pub fn contains(&self, point: &[f64]) -> bool {
+        point
+            .iter()
+            .enumerate()
+            .all(|(j, coord)| self.min[j] <= *coord && *coord <= self.max[j])
+    }
+
+    pub fn overlaps(&self, other: &BoundingBox, tolerance: f64) -> bool {
+        (0..self.min.len()).all(|j| {
+            self.min[j] <= other.max[j] + tolerance && other.min[j] <= self.max[j] + tolerance
+        })
+    }
+
+    pub fn diagonal(&self) -> f64 {
+        vector::distance(&self.min, &self.max)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct OrientedBoundingBox {
+    pub center: Vec<f64>,
+    pub axes: Vec<Vec<f64>>,
+    pub half_extents: Vec<f64>,
+}
+
+// The curve lies inside the convex hull of its control points, and so inside their box.
+pub fn control_box(control_points: &[Vec<f64>]) -> BoundingBox {
+    let dimension = control_points[0].len();
+    let mut bounding_box = BoundingBox {
+        min: vec![f64::MAX; dimension],
+        max: vec![f64::MIN; dimension],
+    };
+    for point in control_points {
+        for (j, coord) in point.iter().enumerate() {
+            bounding_box.min[j] = bounding_box.min[j].min(*coord);
+            bounding_box.max[j] = bounding_box.max[j].max(*coord);
+        }
+    }
+    bounding_box
+}
+
+// Range of a scalar Bézier function over [0, 1]: its extremes are at the end points or at the
+// roots of its derivative.
+fn bezier_range(coefficients: &[f64]) -> (f64, f64) {
+    let n = coefficients.len() - 1;
+    let points: Vec<Vec<f64>> = coefficients.iter().map(|&c| vec![c]).collect();
+    let mut range = (
+        coefficients[0].min(coefficients[n]),
+        coefficients[0].max(coefficients[n]),
+    );
+    if n == 0 {
+        return range;
+    }
+
+    let derivative: Vec<f64> = bezier::hodograph(&points).iter().map(|p| p[0]).collect();
+    for u in bezier::roots(&derivative) {
+        let value = bezier::bezier(&points, u)[0];
+        range = (range.0.min(value), range.1.max(value));
+    }
+    range
+}
+
+fn projected_range(control_points: &[Vec<f64>], direction: &[f64]) -> (f64, f64) {
+    let coefficients: Vec<f64> = control_points
+        .iter()
+        .map(|point| vector::dot(point, direction))
+        .collect();
+    bezier_range(&coefficients)
+}
+
+pub fn bezier_box(control_points: &[Vec<f64>]) -> BoundingBox {
+    let dimension = control_points[0].len();
+    let mut bounding_box = BoundingBox {
+        min: Vec::with_capacity(dimension),
+        max: Vec::with_capacity(dimension),
+    };
+    for j in 0..dimension {
+        let coefficients: Vec<f64> = control_points.iter().map(|point| point[j]).collect();
+        let (min, max) = bezier_range(&coefficients);
+        bounding_box.min.push(min);
+        bounding_box.max.push(max);
+    }
+    bounding_box
+}
+
+pub fn bspline_box(control_points: &[Vec<f64>], degree: usize, knots: &[f64]) -> BoundingBox {
+    bspline::to_bezier_segments(control_points, degree, knots)
+        .iter()
+        .map(|segment| bezier_box(segment))
+        .reduce(|a, b| a.union(&b))
+        .unwrap()
+}
+
+// The tight box of any curve type. NURBS with positive weights stay within the box of their control
+// points, which is used for them instead.
+pub fn shape_box(shape: &Shape) -> BoundingBox {
+    match shape {
+        Shape::Bezier(curve) => bezier_box(&curve.control_points),
+        Shape::BSpline(curve) => bspline_box(&curve.control_points, curve.degree, &curve.knots),
+        Shape::Closed(curve) => {
+            let open = curve.open();
+            bspline_box(&open.control_points, open.degree, &open.knots)
+        }
+        Shape::Nurbs(curve) => control_box(&curve.control_points),
+        Shape::Path(path) => path
+            .segments
+            .iter()
+            .map(shape_box)
+            .reduce(|a, b| a.union(&b))
+            .unwrap(),
+    }
+}
+
+fn cross_2d(o: &[f64], a: &[f64], b: &[f64]) -> f64 {
+    (a[0] - o[0]) * (b[1] - o[1]) - (a[1] - o[1]) * (b[0] - o[0])
+}
+
+// Convex hull of the control points in the xy plane, counter-clockwise (Andrew's monotone chain).
+pub fn convex_hull(control_points: &[Vec<f64>]) -> Vec<Vec<f64>> {
+    let mut points = control_points.to_vec();
+    points.sort_by(|a, b| a[0].total_cmp(&b[0]).then(a[1].total_cmp(&b[1])));
+    points.dedup_by(|a, b| a[0] == b[0] && a[1] == b[1]);
+    if points.len() < 3 {
+        return points;
+    }
+
+    let mut hull: Vec<Vec<f64>> = Vec::with_capacity(2 * points.len());
+    for pass in 0..2 {
+        let start = hull.len();
+        let chain: Vec<&Vec<f64>> = if pass == 0 {
+            points.iter().collect()
+        } else {
+            points.iter().rev().collect()
+        };
+        for point in chain {
+            while hull.len() >= start + 2
+                && cross_2d(&hull[hull.len() - 2], &hull[hull.len() - 1], point) <= 0.0
+            {
+                hull.pop();
+            }
+            hull.push(point.clone());
+        }
+        // The last point of each chain is the first one of the other.
+        hull.pop();
+    }
+    hull
+}
+
+pub fn hull_contains(hull: &[Vec<f64>], point: &[f64]) -> bool {
+    if hull.len() < 3 {
+        return false;
+    }
+    (0..hull.len()).all(|i| cross_2d(&hull[i], &hull[(i + 1) % hull.len()], point) >= 0.0)
+}
+
+// Eigenvectors of a symmetric matrix by cyclic Jacobi rotations.
+fn eigenvectors(mut matrix: Vec<Vec<f64>>) -> Vec<Vec<f64>> {
+    let dimension = matrix.len();
+    let mut vectors: Vec<Vec<f64>> = (0..dimension)
+        .map(|i| {
+            (0..dimension)
+                .map(|j| if i == j { 1.0 } else { 0.0 })
+                .collect()
+        })
+        .collect();
+    let pairs: Vec<(usize, usize)> = (0..dimension)
+        .flat_map(|p| (p + 1..dimension).map(move |q| (p, q)))
+        .collect();
+
+    for _ in 0..50 {
+        let off_diagonal: f64 = pairs.iter().map(|&(p, q)| matrix[p][q].powi(2)).sum();
+        if off_diagonal <= 1e-24 {
+            break;
+        }
+        for &(p, q) in &pairs {
+            if matrix[p][q] == 0.0 {
+                continue;
+            }
+            let theta = (matrix[q][q] - matrix[p][p]) / (2.0 * matrix[p][q]);
+            let t = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+            let t = if theta == 0.0 { 1.0 } else { t };
+            let c = 1.0 / (t * t + 1.0).sqrt();
+            let s = t * c;
+
+            for row in matrix.iter_mut() {
+                let (mkp, mkq) = (row[p], row[q]);
+                row[p] = c * mkp - s * mkq;
+                row[q] = s * mkp + c * mkq;
+            }
+            let (row_p, row_q) = (matrix[p].clone(), matrix[q].clone());
+            matrix[p] = (0..dimension)
+                .map(|k| c * row_p[k] - s * row_q[k])
+                .collect();
+            matrix[q] = (0..dimension)
+                .map(|k| s * row_p[k] + c * row_q[k])
+                .collect();
+            for vector in vectors.iter_mut() {
+                let (vp, vq) = (vector[p], vector[q]);
+                vector[p] = c * vp - s * vq;
+                vector[q] = s * vp + c * vq;
+            }
+        }
+    }
+
+    // Columns of the accumulated rotation are the eigenvectors.
+    (0..dimension)
+        .map(|j| vectors.iter().map(|row| row[j]).collect())
+        .collect()
+}
+
+// Principal axes of the control points, which the curve follows closely, in as many dimensions as
+// the points have.
+fn principal_axes(control_points: &[Vec<f64>]) -> Vec<Vec<f64>> {
+    let dimension = control_points[0].len();
+    let count = control_points.len() as f64;
+    let mean: Vec<f64> = (0..dimension)
+        .map(|j| control_points.iter().map(|p| p[j]).sum::<f64>() / count)
+        .collect();
+
+    let mut covariance = vec![vec![0.0; dimension]; dimension];
+    for point in control_points {
+        let d = vector::sub(point, &mean);
+        for i in 0..dimension {
+            for j in 0..dimension {
+                covariance[i][j] += d[i] * d[j] / count;
+            }
+        }
+    }
+
+    eigenvectors(covariance)
+}
+
+fn oriented_box(segments: &[Vec<Vec<f64>>], axes: Vec<Vec<f64>>) -> OrientedBoundingBox {
+    let mut center = vec![0.0; axes.len()];
+    let mut half_extents = Vec::with_capacity(axes.len());
+
+    for axis in &axes {
+        let (min, max) = segments
+            .iter()
+            .map(|segment| projected_range(segment, axis))
+            .reduce(|a, b| (a.0.min(b.0), a.1.max(b.1)))
+            .unwrap();
+        center = vector::add(&center, &vector::scale(axis, 0.5 * (min + max)));
+        half_extents.push(0.5 * (max - min));
+    }
+
+    OrientedBoundingBox {
+        center,
+        axes,
+        half_extents,
+    }
+}
+
+pub fn bezier_oriented_box(control_points: &[Vec<f64>]) -> OrientedBoundingBox {
+    oriented_box(&[control_points.to_vec()], principal_axes(control_points))
+}
+
+pub fn bspline_oriented_box(
+    control_points: &[Vec<f64>],
+    degree: usize,
+    knots: &[f64],
+) -> OrientedBoundingBox {
+    oriented_box(
+        &bspline::to_bezier_segments(control_points, degree, knots),
+        principal_axes(control_points),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bezier_box_is_tighter_than_the_control_box() {
+        // The curve peaks at y = 1, half way to its middle control point.
+        let control_points = vec![vec![0.0, 0.0], vec![1.0, 2.0], vec![2.0, 0.0]];
+        let tight = bezier_box(&control_points);
+        let loose = control_box(&control_points);
+        assert_eq!(tight.min, vec![0.0, 0.0]);
+        assert_eq!(loose.min, vec![0.0, 0.0]);
+        assert!((tight.max[1] - 1.0).abs() < 1e-12);
+        assert!(tight.max[1] < loose.max[1]);
+        assert!(loose.contains(&tight.max));
+    }
+
+    #[test]
+    fn hull_contains_inner_points_only() {
+        let hull = convex_hull(&[
+            vec![0.0, 0.0, 0.0],
+            vec![2.0, 1.0, 0.0],
+            vec![4.0, 0.0, 0.0],
+            vec![1.0, 0.5, 0.0],
+            vec![2.0, 3.0, 0.0],
+        ]);
+        // The point inside the triangle is not part of the hull.
+        assert_eq!(hull.len(), 3);
+        assert!(hull_contains(&hull, &[2.0, 1.0]));
+        assert!(hull_contains(&hull, &[1.0, 0.5]));
+        assert!(!hull_contains(&hull, &[0.5, 1.5]));
+        assert!(!hull_contains(&hull, &[2.0, -0.1]));
+    }
+
+    #[test]
+    fn oriented_box_recovers_a_rotated_rectangle() {
+        // A 4 by 1 rectangle around (1, 2), turned by 30°, traced by a polyline through its corners.
+        let (sin, cos) = 30.0_f64.to_radians().sin_cos();
+        let corners: Vec<Vec<f64>> = [(-2.0, -0.5), (2.0, -0.5), (2.0, 0.5), (-2.0, 0.5)]
+            .iter()
+            .map(|&(x, y)| vec![1.0 + cos * x - sin * y, 2.0 + sin * x + cos * y])
+            .collect();
+        let knots = bspline::generate_knot_vector(corners.len() - 1, 2);
+        let oriented = bspline_oriented_box(&corners, 2, &knots);
+
+        assert_eq!(oriented.axes.len(), 2);
+        assert!(vector::distance(&oriented.center, &[1.0, 2.0]) < 1e-9);
+        for (axis, half_extent) in oriented.axes.iter().zip(&oriented.half_extents) {
+            let along = vector::dot(axis, &[cos, sin]).abs();
+            let expected = if along > 0.5 { 2.0 } else { 0.5 };
+            assert!((along - 1.0).abs() < 1e-9 || along < 1e-9);
+            assert!((half_extent - expected).abs() < 1e-9);
+        }
+    }
+}
@@ -1,3 +1,5 @@
+use crate::{bezier, vector};
+
 pub fn basis_function(u: f64, i: usize, degree: usize, knots: &[f64]) -> f64 {
     if degree == 1 {
         if knots[i] <= u && u < knots[i + 1] {
@@ -26,10 +28,14 @@ pub fn basis_function(u: f64, i: usize, degree: usize, knots: &[f64]) -> f64 {
 
 pub fn bspline(control_points: &[Vec<f64>], u: f64, degree: usize, knots: &[f64]) -> Vec<f64> {
     let mut p = vec![0.0; 3];
-    let n = control_points.len() - 1;
-    for i in 0..=n {
-        for j in 0..3 {
-            p[j] += control_points[i][j] * basis_function(u, i, degree, knots);
+    for (i, control_point) in control_points.iter().enumerate() {
+        // Each basis function vanishes outside of [knots[i], knots[i + degree]).
+        if u < knots[i] || knots[i + degree] <= u {
+            continue;
+        }
+        let basis = basis_function(u, i, degree, knots);
+        for (coord, control_coord) in p.iter_mut().zip(control_point) {
+            *coord += control_coord * basis;
         }
     }
     p
@@ -43,7 +49,7 @@ pub fn generate_knot_vector(n: usize, degree: usize) -> Vec<f64> {
         } else if degree <= j && j <= n {
             t.push((j - degree + 1) as f64);
         } else {
-            t.push((n - degree + 2) as f64);
+            t.push((n + 2 - degree) as f64);
         }
     }
 
@@ -54,17 +60,20 @@ pub fn dk_bspline(u: f64, i: usize, degree: usize, knots: &[f64], k: usize) -> f
     if k == 0 {
         return basis_function(u, i, degree, knots);
     }
+    // `degree` is the order of the basis, so piecewise constant functions have no derivative.
+    if degree == 1 {
+        return 0.0;
+    }
 
-    let first_term = if i + degree < knots.len() && knots[i + degree] - knots[i] != 0.0 {
-        (degree as f64) / (knots[i + degree] - knots[i])
+    let first_term = if knots[i + degree - 1] - knots[i] != 0.0 {
+        (degree - 1) as f64 / (knots[i + degree - 1] - knots[i])
             * dk_bspline(u, i, degree - 1, knots, k - 1)
     } else {
         0.0
     };
 
-    let second_term = if i + degree + 1 < knots.len() && knots[i + degree + 1] - knots[i + 1] != 0.0
-    {
-        (degree as f64) / (knots[i + degree + 1] - knots[i + 1])
+    let second_term = if knots[i + degree] - knots[i + 1] != 0.0 {
+        (degree - 1) as f64 / (knots[i + degree] - knots[i + 1])
             * dk_bspline(u, i + 1, degree - 1, knots, k - 1)
     } else {
         0.0
@@ -84,6 +93,10 @@ pub fn derivative_bspline(
     let mut p = vec![0.0; dim];
 
     for (i, point) in points.iter().enumerate() {
+        // Like the basis functions, their derivatives vanish outside of [knots[i], knots[i + degree]).
+        if u < knots[i] || knots[i + degree] <= u {
+            continue;
+        }
         let dk_b = dk_bspline(u, i, degree, knots, k);
         for (j, coord) in point.iter().enumerate() {
             p[j] += coord * dk_b;
@@ -92,3 +105,692 @@ pub fn derivative_bspline(
 
     p
 }
+
+pub struct KnotRemoval {
+    pub control_points: Vec<Vec<f64>>,
+    pub knots: Vec<f64>,
+    pub removed: usize,
+    pub max_deviation: f64,
+}
+
+fn knot_multiplicity(knots: &[f64], u: f64) -> usize {
+    knots.iter().filter(|&&t| t == u).count()
+}
+
+pub fn max_deviation(
+    first_control_points: &[Vec<f64>],
+    first_knots: &[f64],
+    second_control_points: &[Vec<f64>],
+    second_knots: &[f64],
+    degree: usize,
+) -> f64 {
+    let n = first_control_points.len() - 1;
+    max_deviation_between(
+        first_control_points,
+        first_knots,
+        second_control_points,
+        second_knots,
+        degree,
+        (first_knots[degree - 1], first_knots[n + 1]),
+    )
+}
+
+fn max_deviation_between(
+    first_control_points: &[Vec<f64>],
+    first_knots: &[f64],
+    second_control_points: &[Vec<f64>],
+    second_knots: &[f64],
+    degree: usize,
+    (from, to): (f64, f64),
+) -> f64 {
+    let samples_per_span = 16;
+    let n = first_control_points.len() - 1;
+    let mut deviation: f64 = 0.0;
+
+    // Both curves share the domain, so sampling the spans of the first one is enough.
+    for span in degree - 1..=n {
+        let (start, end) = (first_knots[span], first_knots[span + 1]);
+        if start == end || end <= from || to <= start {
+            continue;
+        }
+        for j in 0..samples_per_span {
+            let u = start + (end - start) * j as f64 / samples_per_span as f64;
+            let first = bspline(first_control_points, u, degree, first_knots);
+            let second = bspline(second_control_points, u, degree, second_knots);
+            deviation = deviation.max(vector::distance(&first, &second));
+        }
+    }
+
+    deviation
+}
+
+// Removes one occurrence of the knot at index `r` (the last index holding that value), following
+// Piegl & Tiller's algorithm A5.8. The new control points are computed from both ends of the affected
+// range, so the caller is responsible for checking how far the resulting curve deviates. Returns
+// `None` when the knot can't be removed this way: for piecewise constant curves, and when the affected
+// control points r - p - 1..=r - s + 1 don't all exist.
+fn remove_knot_once(
+    control_points: &[Vec<f64>],
+    degree: usize,
+    knots: &[f64],
+    r: usize,
+) -> Option<(Vec<Vec<f64>>, Vec<f64>)> {
+    // `degree` is the order of the basis, as in `basis_function`.
+    if degree < 2 {
+        return None;
+    }
+    let p = degree - 1;
+    let u = knots[r];
+    let s = knot_multiplicity(knots, u);
+    if r < degree || s > degree || r - s + 1 >= control_points.len() {
+        return None;
+    }
+    let first = r - p;
+    let last = r - s;
+    let off = first - 1;
+
+    let mut temp = vec![Vec::new(); last - off + 2];
+    temp[0] = control_points[off].clone();
+    temp[last + 1 - off] = control_points[last + 1].clone();
+
+    let (mut i, mut j) = (first, last);
+    let (mut ii, mut jj) = (1, last - off);
+    while j > i {
+        let alfi = (u - knots[i]) / (knots[i + p + 1] - knots[i]);
+        let alfj = (u - knots[j]) / (knots[j + p + 1] - knots[j]);
+        temp[ii] = control_points[i]
+            .iter()
+            .zip(&temp[ii - 1])
+            .map(|(pi, t)| (pi - (1.0 - alfi) * t) / alfi)
+            .collect();
+        temp[jj] = control_points[j]
+            .iter()
+            .zip(&temp[jj + 1])
+            .map(|(pj, t)| (pj - alfj * t) / (1.0 - alfj))
+            .collect();
+        i += 1;
+        ii += 1;
+        j -= 1;
+        jj -= 1;
+    }
+
+    let mut new_control_points = control_points.to_vec();
+    let (mut i, mut j) = (first, last);
+    while j > i {
+        new_control_points[i] = temp[i - off].clone();
+        new_control_points[j] = temp[j - off].clone();
+        i += 1;
+        j -= 1;
+    }
+
+    // Both ends now hold the same new points, drop the duplicated one in the middle.
+    let fout = (2 * r - s - p) / 2;
+    new_control_points.remove(fout);
+
+    let mut new_knots = knots.to_vec();
+    new_knots.remove(r);
+
+    Some((new_control_points, new_knots))
+}
+
+fn is_interior_knot(control_points: &[Vec<f64>], degree: usize, knots: &[f64], u: f64) -> bool {
+    let n = control_points.len() - 1;
+    knots[degree - 1] < u && u < knots[n + 1]
+}
+
+impl KnotRemoval {
+    fn new(control_points: &[Vec<f64>], knots: &[f64]) -> Self {
+        Self {
+            control_points: control_points.to_vec(),
+            knots: knots.to_vec(),
+            removed: 0,
+            max_deviation: 0.0,
+        }
+    }
+
+    // Tries to remove `u` once from the current curve, comparing against the original one so the
+    // error does not pile up between successive removals.
+    fn try_remove(
+        &mut self,
+        original_control_points: &[Vec<f64>],
+        original_knots: &[f64],
+        degree: usize,
+        u: f64,
+        tolerance: f64,
+    ) -> bool {
+        if !is_interior_knot(&self.control_points, degree, &self.knots, u) {
+            return false;
+        }
+        let r = match self.knots.iter().rposition(|&t| t == u) {
+            Some(r) => r,
+            None => return false,
+        };
+
+        let Some((new_control_points, new_knots)) =
+            remove_knot_once(&self.control_points, degree, &self.knots, r)
+        else {
+            return false;
+        };
+        // Only the control points between r - p and r - s move, so the curve is unchanged outside
+        // of their supports.
+        let s = knot_multiplicity(&self.knots, u);
+        let affected = (self.knots[r + 1 - degree], self.knots[r - s + degree]);
+        let deviation = max_deviation_between(
+            original_control_points,
+            original_knots,
+            &new_control_points,
+            &new_knots,
+            degree,
+            affected,
+        );
+        if deviation > tolerance {
+            return false;
+        }
+
+        self.control_points = new_control_points;
+        self.knots = new_knots;
+        self.removed += 1;
+        self.max_deviation = self.max_deviation.max(deviation);
+        true
+    }
+}
+
+pub fn remove_knot(
+    control_points: &[Vec<f64>],
+    degree: usize,
+    knots: &[f64],
+    u: f64,
+    times: usize,
+    tolerance: f64,
+) -> KnotRemoval {
+    let mut removal = KnotRemoval::new(control_points, knots);
+
+    while removal.removed < times && removal.try_remove(control_points, knots, degree, u, tolerance)
+    {
+    }
+
+    removal
+}
+
+pub fn remove_knots(
+    control_points: &[Vec<f64>],
+    degree: usize,
+    knots: &[f64],
+    tolerance: f64,
+) -> KnotRemoval {
+    let mut removal = KnotRemoval::new(control_points, knots);
+
+    loop {
+        let mut interior_knots: Vec<f64> = removal
+            .knots
+            .iter()
+            .copied()
+            .filter(|&u| is_interior_knot(&removal.control_points, degree, &removal.knots, u))
+            .collect();
+        interior_knots.dedup();
+
+        let removed_before = removal.removed;
+        for u in interior_knots {
+            removal.try_remove(control_points, knots, degree, u, tolerance);
+        }
+
+        if removal.removed == removed_before {
+            break;
+        }
+    }
+
+    removal
+}
+
+// Boehm's knot insertion, repeated `times` times.
+pub fn insert_knot(
+    control_points: &[Vec<f64>],
+    degree: usize,
+    knots: &[f64],
+    u: f64,
+    times: usize,
+) -> (Vec<Vec<f64>>, Vec<f64>) {
+    let p = degree - 1;
+    let mut control_points = control_points.to_vec();
+    let mut knots = knots.to_vec();
+
+    for _ in 0..times {
+        // The end of the domain belongs to the last span, where knots can still follow it.
+        let k = knots
+            .iter()
+            .rposition(|&t| t <= u)
+            .unwrap()
+            .min(control_points.len() - 1);
+        let mut new_control_points = Vec::with_capacity(control_points.len() + 1);
+
+        for i in 0..=control_points.len() {
+            if i + p <= k {
+                new_control_points.push(control_points[i].clone());
+            } else if i > k {
+                new_control_points.push(control_points[i - 1].clone());
+            } else {
+                let alpha = (u - knots[i]) / (knots[i + p] - knots[i]);
+                new_control_points.push(
+                    control_points[i - 1]
+                        .iter()
+                        .zip(&control_points[i])
+                        .map(|(a, b)| (1.0 - alpha) * a + alpha * b)
+                        .collect(),
+                );
+            }
+        }
+
+        knots.insert(k + 1, u);
+        control_points = new_control_points;
+    }
+
+    (control_points, knots)
+}
+
+pub fn to_bezier_segments(
+    control_points: &[Vec<f64>],
+    degree: usize,
+    knots: &[f64],
+) -> Vec<Vec<Vec<f64>>> {
+    let p = degree - 1;
+    let mut control_points = control_points.to_vec();
+    let mut knots = knots.to_vec();
+
+    let mut interior_knots: Vec<f64> = knots
+        .iter()
+        .copied()
+        .filter(|&u| is_interior_knot(&control_points, degree, &knots, u))
+        .collect();
+    interior_knots.dedup();
+
+    for u in interior_knots {
+        let s = knot_multiplicity(&knots, u);
+        if s < p {
+            (control_points, knots) = insert_knot(&control_points, degree, &knots, u, p - s);
+        }
+    }
+
+    // Every interior knot now has multiplicity p or more, so the control points of each non-empty
+    // span are those of its segment. Past multiplicity p the curve breaks at the knot, and the
+    // segments on either side no longer share an end point.
+    let n = control_points.len() - 1;
+    (p..=n)
+        .filter(|&i| knots[i] < knots[i + 1])
+        .map(|i| control_points[i - p..=i].to_vec())
+        .collect()
+}
+
+pub fn from_bezier_segments(
+    segments: &[Vec<Vec<f64>>],
+    continuity: usize,
+    tolerance: f64,
+) -> (usize, Vec<Vec<f64>>, Vec<f64>) {
+    let degree = segments[0].len();
+    let p = degree - 1;
+    assert!(segments.iter().all(|segment| segment.len() == degree));
+
+    let mut control_points = segments[0].clone();
+    for segment in &segments[1..] {
+        control_points.extend_from_slice(&segment[1..]);
+    }
+
+    // Each segment spans one unit of the knot vector, like `generate_knot_vector`, so the Bézier
+    // derivatives carry over unchanged.
+    let mut knots = vec![0.0; degree];
+    for j in 1..segments.len() {
+        knots.extend(std::iter::repeat_n(j as f64, p));
+    }
+    knots.extend(std::iter::repeat_n(segments.len() as f64, degree));
+
+    for j in 1..segments.len() {
+        let removal = remove_knot(
+            &control_points,
+            degree,
+            &knots,
+            j as f64,
+            continuity.min(p),
+            tolerance,
+        );
+        control_points = removal.control_points;
+        knots = removal.knots;
+    }
+
+    (degree, control_points, knots)
+}
+
+// Raises the order by `times`, segment by segment in Bézier form, and then removes the knots the
+// original continuity allows again. Unlike `from_bezier_segments`, the knot values are kept.
+pub fn elevate_degree(
+    control_points: &[Vec<f64>],
+    degree: usize,
+    knots: &[f64],
+    times: usize,
+) -> (Vec<Vec<f64>>, Vec<f64>) {
+    let n = control_points.len() - 1;
+    let mut breakpoints: Vec<f64> = knots
+        .iter()
+        .copied()
+        .filter(|&u| is_interior_knot(control_points, degree, knots, u))
+        .collect();
+    breakpoints.dedup();
+
+    let mut segments = to_bezier_segments(control_points, degree, knots);
+    for _ in 0..times {
+        segments = segments
+            .iter()
+            .map(|segment| bezier::elevate(segment))
+            .collect();
+    }
+
+    let elevated_degree = degree + times;
+    let mut elevated_control_points = segments[0].clone();
+    for segment in &segments[1..] {
+        elevated_control_points.extend_from_slice(&segment[1..]);
+    }
+    let mut elevated_knots = vec![knots[degree - 1]; elevated_degree];
+    for &u in &breakpoints {
+        elevated_knots.extend(std::iter::repeat_n(u, elevated_degree - 1));
+    }
+    elevated_knots.extend(std::iter::repeat_n(knots[n + 1], elevated_degree));
+
+    // A knot of multiplicity s leaves p - s continuous derivatives, and elevation keeps them.
+    for &u in &breakpoints {
+        let removal = remove_knot(
+            &elevated_control_points,
+            elevated_degree,
+            &elevated_knots,
+            u,
+            degree - 1 - knot_multiplicity(knots, u),
+            1e-9,
+        );
+        elevated_control_points = removal.control_points;
+        elevated_knots = removal.knots;
+    }
+
+    (elevated_control_points, elevated_knots)
+}
+
+pub type BSplinePiece = (Vec<Vec<f64>>, Vec<f64>);
+
+// Raises the multiplicity of `u` to the order of the curve, which breaks it into two independent
+// clamped pieces.
+fn separate(
+    control_points: &[Vec<f64>],
+    degree: usize,
+    knots: &[f64],
+    u: f64,
+) -> (BSplinePiece, BSplinePiece) {
+    let s = knot_multiplicity(knots, u);
+    let (control_points, knots) = if s < degree {
+        insert_knot(control_points, degree, knots, u, degree - s)
+    } else {
+        (control_points.to_vec(), knots.to_vec())
+    };
+
+    let r = knots.iter().position(|&t| t == u).unwrap();
+    let left = (control_points[..r].to_vec(), knots[..r + degree].to_vec());
+    let right = (control_points[r..].to_vec(), knots[r..].to_vec());
+
+    (left, right)
+}
+
+pub fn split(
+    control_points: &[Vec<f64>],
+    degree: usize,
+    knots: &[f64],
+    u: f64,
+) -> (BSplinePiece, BSplinePiece) {
+    assert!(is_interior_knot(control_points, degree, knots, u));
+    separate(control_points, degree, knots, u)
+}
+
+// The piece of the curve over `start..end`, with clamped knots.
+pub fn extract(
+    control_points: &[Vec<f64>],
+    degree: usize,
+    knots: &[f64],
+    start: f64,
+    end: f64,
+) -> BSplinePiece {
+    let n = control_points.len() - 1;
+    assert!(knots[degree - 1] <= start && start < end && end <= knots[n + 1]);
+
+    let (_, (control_points, knots)) = separate(control_points, degree, knots, start);
+    let n = control_points.len() - 1;
+    // An end of the domain without full multiplicity, as in periodic knots, still needs clamping.
+    if end < knots[n + 1] || knot_multiplicity(&knots, end) < degree {
+        separate(&control_points, degree, &knots, end).0
+    } else {
+        (control_points, knots)
+    }
+}
+
+pub fn close_control_points(control_points: &[Vec<f64>], degree: usize) -> Vec<Vec<f64>> {
+    let mut closed_control_points = control_points.to_vec();
+    closed_control_points.extend_from_slice(&control_points[..degree - 1]);
+    closed_control_points
+}
+
+// Uniform knots shifted so the periodic domain `knots[degree - 1]..knots[n + 1]` starts at zero.
+// Paired with `close_control_points`, the curve closes with C^{p-1} continuity at the seam.
+pub fn generate_periodic_knot_vector(n: usize, degree: usize) -> Vec<f64> {
+    (0..=n + degree)
+        .map(|j| j as f64 - (degree - 1) as f64)
+        .collect()
+}
+
+pub fn periodic_parameter(u: f64, n: usize, degree: usize, knots: &[f64]) -> f64 {
+    let (start, end) = (knots[degree - 1], knots[n + 1]);
+    start + (u - start).rem_euclid(end - start)
+}
+
+pub fn periodic_bspline(
+    control_points: &[Vec<f64>],
+    u: f64,
+    degree: usize,
+    knots: &[f64],
+) -> Vec<f64> {
+    let n = control_points.len() - 1;
+    bspline(
+        control_points,
+        periodic_parameter(u, n, degree, knots),
+        degree,
+        knots,
+    )
+}
+
+pub fn derivative_periodic_bspline(
+    control_points: &[Vec<f64>],
+    u: f64,
+    k: usize,
+    degree: usize,
+    knots: &[f64],
+) -> Vec<f64> {
+    let n = control_points.len() - 1;
+    derivative_bspline(
+        control_points,
+        periodic_parameter(u, n, degree, knots),
+        k,
+        degree,
+        knots,
+    )
+}
+
+// Reflects the knots so that u maps to `knots[0] + knots[last] - u`, which keeps the domain in place.
+pub fn reverse(control_points: &[Vec<f64>], knots: &[f64]) -> (Vec<Vec<f64>>, Vec<f64>) {
+    let (first, last) = (knots[0], knots[knots.len() - 1]);
+    let reversed_knots = knots.iter().rev().map(|&t| first + last - t).collect();
+    let reversed_control_points = control_points.iter().rev().cloned().collect();
+
+    (reversed_control_points, reversed_knots)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn derivatives_match_finite_differences() {
+        let control_points = vec![
+            vec![0.0, 0.0, 0.0],
+            vec![1.0, 2.0, 0.0],
+            vec![3.0, 3.0, 1.0],
+            vec![4.0, 1.0, 2.0],
+            vec![6.0, 0.0, 1.0],
+            vec![7.0, 2.0, 0.0],
+        ];
+        let knots = [0.0, 0.0, 0.0, 0.0, 0.3, 0.5, 1.0, 1.0, 1.0, 1.0];
+        let (degree, h) = (4, 1e-5);
+        let difference = |u: f64, k: usize| {
+            let at = |u: f64| derivative_bspline(&control_points, u, k, degree, &knots);
+            vector::scale(&vector::sub(&at(u + h), &at(u - h)), 0.5 / h)
+        };
+
+        for u in [0.1, 0.25, 0.4, 0.7, 0.9] {
+            for k in 0..3 {
+                let derivative = derivative_bspline(&control_points, u, k + 1, degree, &knots);
+                assert!(vector::distance(&derivative, &difference(u, k)) < 1e-4);
+            }
+        }
+    }
+
+    fn control_points() -> Vec<Vec<f64>> {
+        vec![
+            vec![0.0, 0.0, 0.0],
+            vec![1.0, 2.0, 0.0],
+            vec![2.0, -1.0, 0.0],
+            vec![3.0, 3.0, 0.0],
+            vec![4.0, 0.0, 0.0],
+            vec![5.0, 1.0, 0.0],
+        ]
+    }
+
+    #[test]
+    fn removes_inserted_knots() {
+        let degree = 4;
+        let knots = generate_knot_vector(5, degree);
+        let (inserted_points, inserted_knots) =
+            insert_knot(&control_points(), degree, &knots, 1.5, 2);
+
+        let removal = remove_knot(&inserted_points, degree, &inserted_knots, 1.5, 2, 1e-9);
+        assert_eq!(removal.removed, 2);
+        assert_eq!(removal.knots, knots);
+        for (point, original) in removal.control_points.iter().zip(control_points()) {
+            assert!(vector::distance(point, &original) < 1e-9);
+        }
+    }
+
+    #[test]
+    fn keeps_knots_beyond_tolerance() {
+        let degree = 4;
+        let knots = generate_knot_vector(5, degree);
+        let removal = remove_knots(&control_points(), degree, &knots, 1e-6);
+        assert_eq!(removal.removed, 0);
+        assert_eq!(removal.knots, knots);
+    }
+
+    #[test]
+    fn piecewise_constant_knots_are_not_removable() {
+        let knots = generate_knot_vector(5, 1);
+        let removal = remove_knots(&control_points(), 1, &knots, 1e-6);
+        assert_eq!(removal.removed, 0);
+    }
+
+    #[test]
+    fn knots_above_full_multiplicity_are_not_removable() {
+        // Multiplicity 4 at order 3 leaves no control points between r - p and r - s.
+        let knots = vec![0.0, 0.0, 0.0, 1.5, 1.5, 1.5, 1.5, 3.0, 3.0, 3.0];
+        let mut points = control_points();
+        points.push(vec![6.0, 0.0, 0.0]);
+        let removal = remove_knot(&points, 3, &knots, 1.5, 1, 1.0);
+        assert_eq!(removal.removed, 0);
+    }
+
+    #[test]
+    fn elevation_keeps_the_continuity_at_the_knots() {
+        // A simple knot at 1 and a double one at 2 leave C2 and C1 continuity.
+        let control_points = vec![
+            vec![0.0, 0.0, 0.0],
+            vec![1.0, 2.0, 0.0],
+            vec![2.0, -1.0, 0.0],
+            vec![3.0, 3.0, 0.0],
+            vec![4.0, 0.0, 0.0],
+            vec![5.0, 1.0, 0.0],
+            vec![6.0, -2.0, 0.0],
+        ];
+        let degree = 4;
+        let knots = [0.0, 0.0, 0.0, 0.0, 1.0, 2.0, 2.0, 3.0, 3.0, 3.0, 3.0];
+
+        for times in 1..=2 {
+            let (elevated_points, elevated_knots) =
+                elevate_degree(&control_points, degree, &knots, times);
+            assert_eq!(knot_multiplicity(&elevated_knots, 1.0), 1 + times);
+            assert_eq!(knot_multiplicity(&elevated_knots, 2.0), 2 + times);
+            assert_eq!(elevated_points.len() + degree + times, elevated_knots.len());
+
+            for u in [0.0, 0.5, 1.0, 1.7, 2.0, 2.9] {
+                let original = bspline(&control_points, u, degree, &knots);
+                let elevated = bspline(&elevated_points, u, degree + times, &elevated_knots);
+                assert!(vector::distance(&original, &elevated) < 1e-9);
+            }
+        }
+    }
+
+    // Each segment must trace its knot span of the curve.
+    fn assert_segments_trace_the_curve(control_points: &[Vec<f64>], degree: usize, knots: &[f64]) {
+        let segments = to_bezier_segments(control_points, degree, knots);
+        let mut spans: Vec<f64> = knots[degree - 1..=control_points.len()].to_vec();
+        spans.dedup();
+        assert_eq!(segments.len(), spans.len() - 1);
+
+        for (segment, span) in segments.iter().zip(spans.windows(2)) {
+            assert_eq!(segment.len(), degree);
+            for i in 0..10 {
+                let t = i as f64 / 10.0;
+                let u = span[0] + (span[1] - span[0]) * t;
+                let point = bspline(control_points, u, degree, knots);
+                assert!(vector::distance(&bezier::bezier(segment, t), &point) < 1e-9);
+            }
+        }
+    }
+
+    #[test]
+    fn bezier_segments_round_trip() {
+        let control_points = vec![
+            vec![0.0, 0.0, 0.0],
+            vec![1.0, 2.0, 0.0],
+            vec![2.0, -1.0, 1.0],
+            vec![3.0, 3.0, 0.0],
+            vec![4.0, 0.0, 2.0],
+            vec![5.0, 1.0, 0.0],
+            vec![6.0, -2.0, 1.0],
+        ];
+        let degree = 4;
+        let knots = [0.0, 0.0, 0.0, 0.0, 1.0, 2.0, 2.0, 3.0, 3.0, 3.0, 3.0];
+        assert_segments_trace_the_curve(&control_points, degree, &knots);
+
+        let segments = to_bezier_segments(&control_points, degree, &knots);
+        let (joined_degree, joined_points, joined_knots) =
+            from_bezier_segments(&segments, degree - 1, 1e-9);
+        assert_eq!(joined_degree, degree);
+        for i in 0..30 {
+            let u = i as f64 / 10.0;
+            let original = bspline(&control_points, u, degree, &knots);
+            let joined = bspline(&joined_points, u, degree, &joined_knots);
+            assert!(
+                vector::distance(&original, &joined) < 1e-9,
+                "differs at {u}"
+            );
+        }
+    }
+
+    #[test]
+    fn bezier_segments_split_at_breaks() {
+        // A knot of multiplicity 3 at order 3 breaks the curve at 1, and the one at 3 is simple.
+        let knots = [0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 3.0, 4.0, 4.0, 4.0];
+        let mut points = control_points();
+        points.push(vec![6.0, 0.0, 0.0]);
+        assert_segments_trace_the_curve(&points, 3, &knots);
+        assert_eq!(to_bezier_segments(&points, 3, &knots).len(), 3);
+    }
+}
@@ -0,0 +1,556 @@
+use crate::bspline;
+use crate::curve::{BSplineCurve, Curve};
+use crate::differential::{self, FrenetFrame};
+use crate::interpolation::{self, Parametrization};
+use crate::surface::{self, BSplineSurface, NurbsSurface};
+use crate::vector;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Frames {
+    Frenet,
+    RotationMinimizing,
+}
+
+// Knots of the rescaled curves closer than this are taken for the same knot.
+const KNOT_TOLERANCE: f64 = 1e-9;
+
+// Brings the curves to the same order, the domain [0, 1] and the union of all their knots, so
+// their control points can be stacked into one net.
+pub fn compatible(curves: &[BSplineCurve]) -> Vec<BSplineCurve> {
+    let degree = curves.iter().map(|curve| curve.degree).max().unwrap();
+    let curves: Vec<BSplineCurve> = curves
+        .iter()
+        .map(|curve| {
+            let (control_points, knots) = bspline::elevate_degree(
+                &curve.control_points,
+                curve.degree,
+                &curve.knots,
+                degree - curve.degree,
+            );
+            BSplineCurve::with_knots(control_points, degree, knots).with_domain(0.0, 1.0)
+        })
+        .collect();
+
+    let mut knots: Vec<f64> = curves
+        .iter()
+        .flat_map(|curve| curve.knots.iter().copied())
+        .collect();
+    knots.sort_by(f64::total_cmp);
+    knots.dedup_by(|next, previous| *next - *previous <= KNOT_TOLERANCE);
+    *knots.last_mut().unwrap() = 1.0;
+
+    // Rescaling rounds the knots, so nearly equal ones are snapped to the merged value rather than
+    // inserted next to it.
+    let snap = |u: f64| {
+        *knots
+            .iter()
+            .min_by(|a, b| (*a - u).abs().total_cmp(&(*b - u).abs()))
+            .unwrap()
+    };
+    let curves: Vec<BSplineCurve> = curves
+        .into_iter()
+        .map(|curve| {
+            let knots = curve.knots.iter().map(|&u| snap(u)).collect();
+            BSplineCurve::with_knots(curve.control_points, degree, knots)
+        })
+        .collect();
+    let multiplicity =
+        |curve: &BSplineCurve, u: f64| curve.knots.iter().filter(|&&t| t == u).count();
+
+    curves
+        .iter()
+        .map(|curve| {
+            let (mut control_points, mut curve_knots) =
+                (curve.control_points.clone(), curve.knots.clone());
+            for &u in &knots {
+                let missing = curves
+                    .iter()
+                    .map(|other| multiplicity(other, u))
+                    .max()
+                    .unwrap()
+                    - multiplicity(curve, u);
+                if missing > 0 {
+                    (control_points, curve_knots) =
+                        bspline::insert_knot(&control_points, degree, &curve_knots, u, missing);
+                }
+            }
+            BSplineCurve::with_knots(control_points, degree, curve_knots)
+        })
+        .collect()
+}
+
+// Interpolates every column of stacked, compatible control polygons at the same parameters, which
+// gives all of them the same knots in `u` (Piegl & Tiller, section 10.3).
+fn skin(sections: &[BSplineCurve], degree: usize, parameters: &[f64]) -> BSplineSurface {
+    let columns: Vec<BSplineCurve> = surface::transpose(
+        &sections
+            .iter()
+            .map(|section| section.control_points.clone())
+            .collect::<Vec<_>>(),
+    )
+    .iter()
+    .map(|column| interpolation::interpolate_at(column, degree, parameters))
+    .collect();
+
+    let control_points = surface::transpose(
+        &columns
+            .iter()
+            .map(|column| column.control_points.clone())
+            .collect::<Vec<_>>(),
+    );
+    BSplineSurface::with_knots(
+        control_points,
+        degree,
+        sections[0].degree,
+        columns[0].knots.clone(),
+        sections[0].knots.clone(),
+    )
+}
+
+// The section parameters are averaged over the columns of the net, so that no column is favoured.
+pub fn loft(
+    sections: &[BSplineCurve],
+    degree: usize,
+    parametrization: Parametrization,
+) -> BSplineSurface {
+    let sections = compatible(sections);
+    let columns = surface::transpose(
+        &sections
+            .iter()
+            .map(|section| section.control_points.clone())
+            .collect::<Vec<_>>(),
+    );
+
+    let mut parameters = vec![0.0; sections.len()];
+    for column in &columns {
+        for (parameter, column_parameter) in parameters
+            .iter_mut()
+            .zip(interpolation::parameters(column, parametrization))
+        {
+            *parameter += column_parameter / columns.len() as f64;
+        }
+    }
+
+    skin(&sections, degree.min(sections.len()), &parameters)
+}
+
+pub fn ruled(first: &BSplineCurve, second: &BSplineCurve) -> BSplineSurface {
+    loft(
+        &[first.clone(), second.clone()],
+        2,
+        Parametrization::Uniform,
+    )
+}
+
+fn perpendicular(direction: &[f64]) -> Vec<f64> {
+    let axis = (0..3)
+        .min_by(|&a, &b| direction[a].abs().total_cmp(&direction[b].abs()))
+        .unwrap();
+    let mut unit = vec![0.0; 3];
+    unit[axis] = 1.0;
+    vector::normalize(&vector::cross(direction, &unit))
+}
+
+fn reflect(p: &[f64], direction: &[f64]) -> Vec<f64> {
+    let length = vector::dot(direction, direction);
+    if length <= f64::EPSILON {
+        return p.to_vec();
+    }
+    vector::sub(
+        p,
+        &vector::scale(direction, 2.0 * vector::dot(direction, p) / length),
+    )
+}
+
+fn frame(tangent: Vec<f64>, normal: Vec<f64>) -> FrenetFrame {
+    let binormal = vector::cross(&tangent, &normal);
+    FrenetFrame {
+        tangent,
+        normal,
+        binormal,
+    }
+}
+
+// Where the Frenet frame is undefined, e.g. along straight stretches, the previous one is kept.
+// Rotation-minimising frames use the double reflection method (Wang et al. 2008) and start from the
+// Frenet frame, or any normal if the path starts straight.
+fn path_frames(path: &impl Curve, parameters: &[f64], frames: Frames) -> Vec<FrenetFrame> {
+    let tangent = differential::tangent(path, parameters[0]);
+    let first = differential::frenet_frame(path, parameters[0])
+        .unwrap_or_else(|| frame(tangent.clone(), perpendicular(&tangent)));
+
+    let mut path_frames = vec![first];
+    for pair in parameters.windows(2) {
+        let previous = &path_frames[path_frames.len() - 1];
+        let tangent = differential::tangent(path, pair[1]);
+        let next = match frames {
+            Frames::Frenet => differential::frenet_frame(path, pair[1]).unwrap_or_else(|| {
+                let normal = vector::sub(
+                    &previous.normal,
+                    &vector::scale(&tangent, vector::dot(&previous.normal, &tangent)),
+                );
+                frame(tangent, vector::normalize(&normal))
+            }),
+            Frames::RotationMinimizing => {
+                let step = vector::sub(&path.point(pair[1]), &path.point(pair[0]));
+                let reflected_normal = reflect(&previous.normal, &step);
+                let reflected_tangent = reflect(&previous.tangent, &step);
+                let normal = reflect(
+                    &reflected_normal,
+                    &vector::sub(&tangent, &reflected_tangent),
+                );
+                frame(tangent, normal)
+            }
+        };
+        path_frames.push(next);
+    }
+
+    path_frames
+}
+
+// The profile is given in the local coordinates of the frames: x along the normal, y along the
+// binormal and z along the tangent of the path. The surface interpolates `sections` copies of it.
+pub fn sweep(
+    profile: &BSplineCurve,
+    path: &impl Curve,
+    frames: Frames,
+    sections: usize,
+    degree: usize,
+) -> BSplineSurface {
+    let parameters = differential::sample_parameters(path, sections);
+    let placed: Vec<BSplineCurve> = parameters
+        .iter()
+        .zip(path_frames(path, &parameters, frames))
+        .map(|(&u, frame)| {
+            let origin = path.point(u);
+            let control_points = profile
+                .control_points
+                .iter()
+                .map(|p| {
+                    let offset = vector::add(
+                        &vector::add(
+                            &vector::scale(&frame.normal, p[0]),
+                            &vector::scale(&frame.binormal, p[1]),
+                        ),
+                        &vector::scale(&frame.tangent, p[2]),
+                    );
+                    vector::add(&origin, &offset)
+                })
+                .collect();
+            BSplineCurve::with_knots(control_points, profile.degree, profile.knots.clone())
+        })
+        .collect();
+
+    let (start, end) = path.domain();
+    let parameters: Vec<f64> = parameters
+        .iter()
+        .map(|u| (u - start) / (end - start))
+        .collect();
+    skin(&placed, degree.min(sections), &parameters)
+}
+
+// Rodrigues' rotation of `p` by `angle` around the axis through `axis_point`.
+fn rotate(p: &[f64], axis_point: &[f64], axis: &[f64], angle: f64) -> Vec<f64> {
+    let q = vector::sub(p, axis_point);
+    let (sin, cos) = angle.sin_cos();
+    let rotated = vector::add(
+        &vector::add(
+            &vector::scale(&q, cos),
+            &vector::scale(&vector::cross(axis, &q), sin),
+        ),
+        &vector::scale(axis, vector::dot(axis, &q) * (1.0 - cos)),
+    );
+    vector::add(axis_point, &rotated)
+}
+
+// The exact surface of revolution (Piegl & Tiller, algorithm A8.1): every control point of the
+// profile sweeps a circle, made of arcs of at most a quarter turn. Each arc is a rational quadratic
+// whose middle control point, where the tangents at its ends meet, weighs the cosine of half the arc.
+// `u` runs along the circles and `v` along the profile.
+pub fn revolve(
+    profile: &BSplineCurve,
+    axis_point: &[f64],
+    axis_direction: &[f64],
+    angle: f64,
+) -> NurbsSurface {
+    let axis = vector::normalize(axis_direction);
+    let arcs = ((angle.abs() / std::f64::consts::FRAC_PI_2).ceil() as usize).max(1);
+    let step = angle / arcs as f64;
+    let middle_weight = (step / 2.0).cos();
+
+    let mut u_knots = vec![0.0; 3];
+    for i in 1..arcs {
+        let u = i as f64 / arcs as f64;
+        u_knots.extend([u, u]);
+    }
+    u_knots.extend([1.0; 3]);
+
+    let (control_points, weights) = (0..=2 * arcs)
+        .map(|i| {
+            let rotated = profile
+                .control_points
+                .iter()
+                .map(|p| rotate(p, axis_point, &axis, step * i as f64 / 2.0));
+            if i % 2 == 0 {
+                (rotated.collect(), vec![1.0; profile.control_points.len()])
+            } else {
+                let middle = rotated
+                    .zip(&profile.control_points)
+                    .map(|(rotated, p)| {
+                        let offset = vector::sub(p, axis_point);
+                        let centre = vector::add(
+                            axis_point,
+                            &vector::scale(&axis, vector::dot(&axis, &offset)),
+                        );
+                        vector::add(
+                            &centre,
+                            &vector::scale(&vector::sub(&rotated, &centre), 1.0 / middle_weight),
+                        )
+                    })
+                    .collect();
+                (middle, vec![middle_weight; profile.control_points.len()])
+            }
+        })
+        .unzip();
+
+    NurbsSurface::with_knots(
+        control_points,
+        weights,
+        3,
+        profile.degree,
+        u_knots,
+        profile.knots.clone(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::surface::Surface;
+
+    // A profile in the xz plane, at distance 1 + z² / 4 from the z axis.
+    fn profile() -> BSplineCurve {
+        BSplineCurve::with_knots(
+            vec![
+                vec![1.0, 0.0, 0.0],
+                vec![1.0, 0.0, 1.0],
+                vec![2.0, 0.0, 2.0],
+            ],
+            3,
+            vec![0.0, 0.0, 0.0, 1.0, 1.0, 1.0],
+        )
+    }
+
+    fn assert_orthonormal(frame: &FrenetFrame) {
+        let axes = [&frame.tangent, &frame.normal, &frame.binormal];
+        for (i, a) in axes.iter().enumerate() {
+            assert!((vector::norm(a) - 1.0).abs() < 1e-9, "axis {i} is not unit");
+            for b in &axes[i + 1..] {
+                assert!(vector::dot(a, b).abs() < 1e-9, "axes are not orthogonal");
+            }
+        }
+    }
+
+    // Starts along a straight stretch, where the Frenet frame is undefined, then turns and climbs.
+    fn path() -> BSplineCurve {
+        BSplineCurve::new(
+            vec![
+                vec![0.0, 0.0, 0.0],
+                vec![1.0, 0.0, 0.0],
+                vec![2.0, 0.0, 0.0],
+                vec![3.0, 1.0, 0.0],
+                vec![3.0, 2.0, 1.0],
+                vec![2.0, 3.0, 2.0],
+            ],
+            3,
+        )
+    }
+
+    #[test]
+    fn compatible_merges_nearly_equal_knots() {
+        let first = BSplineCurve::with_knots(
+            vec![
+                vec![0.0, 0.0],
+                vec![1.0, 1.0],
+                vec![2.0, 0.0],
+                vec![3.0, 1.0],
+            ],
+            3,
+            vec![0.0, 0.0, 0.0, 0.5, 1.0, 1.0, 1.0],
+        );
+        let second = BSplineCurve::with_knots(
+            vec![
+                vec![0.0, 2.0],
+                vec![1.0, 3.0],
+                vec![2.0, 2.0],
+                vec![3.0, 3.0],
+            ],
+            3,
+            vec![0.0, 0.0, 0.0, 0.5 + 1e-12, 1.0, 1.0, 1.0],
+        );
+        let curves = compatible(&[first.clone(), second.clone()]);
+        for (curve, original) in curves.iter().zip([&first, &second]) {
+            assert_eq!(curve.control_points.len(), 4, "the net was inflated");
+            assert_eq!(curve.knots, curves[0].knots);
+            for i in 0..=10 {
+                let u = i as f64 / 10.0;
+                assert!(vector::distance(&curve.point(u), &original.point(u)) < 1e-9);
+            }
+        }
+    }
+
+    #[test]
+    fn loft_interpolates_its_sections() {
+        let sections = [
+            BSplineCurve::new(
+                vec![
+                    vec![0.0, 0.0, 0.0],
+                    vec![1.0, 1.0, 0.0],
+                    vec![2.0, 0.0, 0.0],
+                ],
+                2,
+            ),
+            BSplineCurve::new(
+                vec![
+                    vec![0.0, 0.0, 1.0],
+                    vec![0.5, 2.0, 1.0],
+                    vec![1.5, -1.0, 1.0],
+                    vec![2.0, 1.0, 1.0],
+                ],
+                3,
+            ),
+            BSplineCurve::new(
+                vec![
+                    vec![0.0, 1.0, 2.0],
+                    vec![1.0, 0.0, 3.0],
+                    vec![1.5, 2.0, 2.0],
+                    vec![2.0, 0.0, 2.0],
+                    vec![2.5, 1.0, 2.0],
+                ],
+                3,
+            ),
+        ];
+        let surface = loft(&sections, 3, Parametrization::Uniform);
+        for (k, section) in sections.iter().enumerate() {
+            let u = k as f64 / 2.0;
+            let (start, end) = section.domain();
+            for j in 0..=10 {
+                let v = j as f64 / 10.0;
+                let expected = section.point(start + (end - start) * v);
+                assert!(
+                    vector::distance(&surface.point(u, v), &expected) < 1e-9,
+                    "section {k} is missed at {v}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn ruled_surfaces_are_linear_across_the_edges() {
+        let first = BSplineCurve::new(
+            vec![
+                vec![0.0, 0.0, 0.0],
+                vec![1.0, 1.0, 0.0],
+                vec![2.0, 0.0, 0.0],
+                vec![3.0, 1.0, 0.0],
+            ],
+            3,
+        );
+        let second = BSplineCurve::new(
+            vec![
+                vec![0.0, 0.0, 1.0],
+                vec![1.0, 2.0, 2.0],
+                vec![3.0, 0.0, 1.0],
+            ],
+            2,
+        );
+        let surface = ruled(&first, &second);
+        let (first, second) = (first.with_domain(0.0, 1.0), second.with_domain(0.0, 1.0));
+        for i in 0..=4 {
+            for j in 0..=10 {
+                let (u, v) = (i as f64 / 4.0, j as f64 / 10.0);
+                let expected = vector::add(
+                    &vector::scale(&first.point(v), 1.0 - u),
+                    &vector::scale(&second.point(v), u),
+                );
+                assert!(vector::distance(&surface.point(u, v), &expected) < 1e-9);
+            }
+        }
+    }
+
+    #[test]
+    fn sweep_frames_are_orthonormal() {
+        let path = path();
+        let parameters = differential::sample_parameters(&path, 50);
+        for frames in [Frames::Frenet, Frames::RotationMinimizing] {
+            for frame in path_frames(&path, &parameters, frames) {
+                assert_orthonormal(&frame);
+            }
+        }
+    }
+
+    #[test]
+    fn sweep_places_the_profile_in_the_frames() {
+        let path = path();
+        // A segment along the normal, through the path.
+        let profile = BSplineCurve::new(vec![vec![1.0, 0.0, 0.0], vec![-1.0, 0.0, 0.0]], 2);
+        let surface = sweep(&profile, &path, Frames::RotationMinimizing, 9, 3);
+        let parameters = differential::sample_parameters(&path, 9);
+        let (start, end) = path.domain();
+        for &t in &parameters {
+            let u = (t - start) / (end - start);
+            let (origin, tangent) = (path.point(t), differential::tangent(&path, t));
+            for j in 0..=4 {
+                let v = j as f64 / 4.0;
+                let offset = vector::sub(&surface.point(u, v), &origin);
+                assert!((vector::norm(&offset) - (1.0 - 2.0 * v).abs()).abs() < 1e-8);
+                assert!(vector::dot(&offset, &tangent).abs() < 1e-8);
+            }
+        }
+    }
+
+    #[test]
+    fn revolve_is_exact() {
+        let angle = 1.5 * std::f64::consts::PI;
+        let surface = revolve(&profile(), &[0.0, 0.0, 0.0], &[0.0, 0.0, 1.0], angle);
+        for i in 0..=20 {
+            for j in 0..=10 {
+                let (u, v) = (i as f64 / 20.0, j as f64 / 10.0);
+                let point = surface.point(u, v);
+                let expected = profile().point(v);
+                assert!((point[2] - expected[2]).abs() < 1e-9);
+                assert!((point[0].hypot(point[1]) - expected[0]).abs() < 1e-9);
+            }
+        }
+
+        let end = rotate(&profile().point(0.5), &[0.0; 3], &[0.0, 0.0, 1.0], angle);
+        assert!(vector::distance(&surface.point(1.0, 0.5), &end) < 1e-8);
+    }
+
+    #[test]
+    fn revolved_derivatives_match_finite_differences() {
+        let surface = revolve(&profile(), &[0.0, 0.0, 0.0], &[0.0, 0.0, 1.0], 2.0);
+        let h = 1e-5;
+        let (u, v) = (0.3, 0.6);
+        let central = |du: f64, dv: f64| {
+            vector::scale(
+                &vector::sub(
+                    &surface.point(u + du, v + dv),
+                    &surface.point(u - du, v - dv),
+                ),
+                0.5 / h,
+            )
+        };
+        assert!(vector::distance(&surface.derivative(u, v, 1, 0), &central(h, 0.0)) < 1e-6);
+        assert!(vector::distance(&surface.derivative(u, v, 0, 1), &central(0.0, h)) < 1e-6);
+
+        let mixed = vector::scale(
+            &vector::sub(
+                &vector::sub(&surface.point(u + h, v + h), &surface.point(u + h, v - h)),
+                &vector::sub(&surface.point(u - h, v + h), &surface.point(u - h, v - h)),
+            ),
+            0.25 / (h * h),
+        );
+        assert!(vector::distance(&surface.derivative(u, v, 1, 1), &mixed) < 1e-4);
+    }
+}
@@ -0,0 +1,469 @@
+use crate::{bezier, bspline, nurbs};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+pub trait Curve {
+    fn domain(&self) -> (f64, f64);
+
+    fn point(&self, u: f64) -> Vec<f64>;
+
+    fn derivative(&self, u: f64, k: usize) -> Vec<f64>;
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct BezierCurve {
+    pub control_points: Vec<Vec<f64>>,
+}
+
+impl BezierCurve {
+    pub fn new(control_points: Vec<Vec<f64>>) -> Self {
+        Self { control_points }
+    }
+
+    pub fn reverse(&self) -> Self {
+        Self::new(bezier::reverse(&self.control_points))
+    }
+}
+
+impl Curve for BezierCurve {
+    fn domain(&self) -> (f64, f64) {
+        (0.0, 1.0)
+    }
+
+    fn point(&self, u: f64) -> Vec<f64> {
+        bezier::bezier(&self.control_points, u)
+    }
+
+    fn derivative(&self, u: f64, k: usize) -> Vec<f64> {
+        bezier::derivative_bezier(&self.control_points, u, k)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct BSplineCurve {
+    pub control_points: Vec<Vec<f64>>,
+    pub degree: usize,
+    pub knots: Vec<f64>,
+}
+
+impl BSplineCurve {
+    pub fn new(control_points: Vec<Vec<f64>>, degree: usize) -> Self {
+        let knots = bspline::generate_knot_vector(control_points.len() - 1, degree);
+        Self::with_knots(control_points, degree, knots)
+    }
+
+    pub fn with_knots(control_points: Vec<Vec<f64>>, degree: usize, knots: Vec<f64>) -> Self {
+        assert_eq!(knots.len(), control_points.len() + degree);
+        Self {
+            control_points,
+            degree,
+            knots,
+        }
+    }
+
+    pub fn reverse(&self) -> Self {
+        let (control_points, knots) = bspline::reverse(&self.control_points, &self.knots);
+        Self::with_knots(control_points, self.degree, knots)
+    }
+
+    pub fn with_domain(&self, start: f64, end: f64) -> Self {
+        let knots = remap_knots(&self.knots, self.domain(), start, end);
+        Self::with_knots(self.control_points.clone(), self.degree, knots)
+    }
+
+    // The basis functions are zero at the very end of the domain, so it is evaluated just before it,
+    // the same way `main` samples the curve.
+    fn clamp_parameter(&self, u: f64) -> f64 {
+        let (start, end) = self.domain();
+        u.clamp(start, end - 1e-10)
+    }
+}
+
+impl Curve for BSplineCurve {
+    fn domain(&self) -> (f64, f64) {
+        let n = self.control_points.len() - 1;
+        (self.knots[self.degree - 1], self.knots[n + 1])
+    }
+
+    fn point(&self, u: f64) -> Vec<f64> {
+        bspline::bspline(
+            &self.control_points,
+            self.clamp_parameter(u),
+            self.degree,
+            &self.knots,
+        )
+    }
+
+    fn derivative(&self, u: f64, k: usize) -> Vec<f64> {
+        bspline::derivative_bspline(
+            &self.control_points,
+            self.clamp_parameter(u),
+            k,
+            self.degree,
+            &self.knots,
+        )
+    }
+}
+
+// A B-Spline closed through its control points in order and back to the first. It is evaluated
+// over `close_control_points` and periodic knots, wrapping the parameter around the domain
+// `0..control_points.len()`, so the seam is as smooth as the rest of the curve.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ClosedBSplineCurve {
+    pub control_points: Vec<Vec<f64>>,
+    pub degree: usize,
+}
+
+impl ClosedBSplineCurve {
+    pub fn new(control_points: Vec<Vec<f64>>, degree: usize) -> Self {
+        assert!(degree >= 1 && degree <= control_points.len());
+        Self {
+            control_points,
+            degree,
+        }
+    }
+
+    fn periodic(&self) -> (Vec<Vec<f64>>, Vec<f64>) {
+        let control_points = bspline::close_control_points(&self.control_points, self.degree);
+        let knots = bspline::generate_periodic_knot_vector(control_points.len() - 1, self.degree);
+        (control_points, knots)
+    }
+
+    // The same loop as an ordinary clamped B-Spline, which starts and ends at the seam.
+    pub fn open(&self) -> BSplineCurve {
+        let (control_points, knots) = self.periodic();
+        let (start, end) = self.domain();
+        let (control_points, knots) =
+            bspline::extract(&control_points, self.degree, &knots, start, end);
+        BSplineCurve::with_knots(control_points, self.degree, knots)
+    }
+}
+
+impl Curve for ClosedBSplineCurve {
+    fn domain(&self) -> (f64, f64) {
+        (0.0, self.control_points.len() as f64)
+    }
+
+    fn point(&self, u: f64) -> Vec<f64> {
+        let (control_points, knots) = self.periodic();
+        bspline::periodic_bspline(&control_points, u, self.degree, &knots)
+    }
+
+    fn derivative(&self, u: f64, k: usize) -> Vec<f64> {
+        let (control_points, knots) = self.periodic();
+        bspline::derivative_periodic_bspline(&control_points, u, k, self.degree, &knots)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NurbsCurve {
+    pub control_points: Vec<Vec<f64>>,
+    pub weights: Vec<f64>,
+    pub degree: usize,
+    pub knots: Vec<f64>,
+}
+
+impl NurbsCurve {
+    pub fn new(control_points: Vec<Vec<f64>>, weights: Vec<f64>, degree: usize) -> Self {
+        let knots = bspline::generate_knot_vector(control_points.len() - 1, degree);
+        Self::with_knots(control_points, weights, degree, knots)
+    }
+
+    pub fn with_knots(
+        control_points: Vec<Vec<f64>>,
+        weights: Vec<f64>,
+        degree: usize,
+        knots: Vec<f64>,
+    ) -> Self {
+        assert_eq!(weights.len(), control_points.len());
+        assert_eq!(knots.len(), control_points.len() + degree);
+        Self {
+            control_points,
+            weights,
+            degree,
+            knots,
+        }
+    }
+
+    pub fn with_domain(&self, start: f64, end: f64) -> Self {
+        let knots = remap_knots(&self.knots, self.domain(), start, end);
+        Self::with_knots(
+            self.control_points.clone(),
+            self.weights.clone(),
+            self.degree,
+            knots,
+        )
+    }
+
+    fn clamp_parameter(&self, u: f64) -> f64 {
+        let (start, end) = self.domain();
+        u.clamp(start, end - 1e-10)
+    }
+}
+
+impl Curve for NurbsCurve {
+    fn domain(&self) -> (f64, f64) {
+        let n = self.control_points.len() - 1;
+        (self.knots[self.degree - 1], self.knots[n + 1])
+    }
+
+    fn point(&self, u: f64) -> Vec<f64> {
+        nurbs::nurbs(
+            &self.control_points,
+            &self.weights,
+            self.clamp_parameter(u),
+            self.degree,
+            &self.knots,
+        )
+    }
+
+    fn derivative(&self, u: f64, k: usize) -> Vec<f64> {
+        nurbs::derivative_nurbs(
+            &self.control_points,
+            &self.weights,
+            self.clamp_parameter(u),
+            k,
+            self.degree,
+            &self.knots,
+        )
+    }
+}
+
+// Any of the curve types, so curves of different kinds can be stored and joined together.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(tag = "type", rename_all = "lowercase")
+)]
+pub enum Shape {
+    Bezier(BezierCurve),
+    BSpline(BSplineCurve),
+    Closed(ClosedBSplineCurve),
+    Nurbs(NurbsCurve),
+    Path(Path),
+}
+
+impl Shape {
+    // A path lists the control points of its segments one after the other.
+    pub fn control_points(&self) -> Vec<Vec<f64>> {
+        match self {
+            Shape::Bezier(curve) => curve.control_points.clone(),
+            Shape::BSpline(curve) => curve.control_points.clone(),
+            Shape::Closed(curve) => curve.control_points.clone(),
+            Shape::Nurbs(curve) => curve.control_points.clone(),
+            Shape::Path(path) => path
+                .segments
+                .iter()
+                .flat_map(|segment| segment.control_points())
+                .collect(),
+        }
+    }
+}
+
+impl Curve for Shape {
+    fn domain(&self) -> (f64, f64) {
+        match self {
+            Shape::Bezier(curve) => curve.domain(),
+            Shape::BSpline(curve) => curve.domain(),
+            Shape::Closed(curve) => curve.domain(),
+            Shape::Nurbs(curve) => curve.domain(),
+            Shape::Path(path) => path.domain(),
+        }
+    }
+
+    fn point(&self, u: f64) -> Vec<f64> {
+        match self {
+            Shape::Bezier(curve) => curve.point(u),
+            Shape::BSpline(curve) => curve.point(u),
+            Shape::Closed(curve) => curve.point(u),
+            Shape::Nurbs(curve) => curve.point(u),
+            Shape::Path(path) => path.point(u),
+        }
+    }
+
+    fn derivative(&self, u: f64, k: usize) -> Vec<f64> {
+        match self {
+            Shape::Bezier(curve) => curve.derivative(u, k),
+            Shape::BSpline(curve) => curve.derivative(u, k),
+            Shape::Closed(curve) => curve.derivative(u, k),
+            Shape::Nurbs(curve) => curve.derivative(u, k),
+            Shape::Path(path) => path.derivative(u, k),
+        }
+    }
+}
+
+// Segments placed one after the other, each keeping the length of its own domain, the same way
+// the curvature plots continue a join.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Path {
+    pub segments: Vec<Shape>,
+}
+
+impl Path {
+    pub fn new(segments: Vec<Shape>) -> Self {
+        assert!(!segments.is_empty());
+        Self { segments }
+    }
+
+    // The segment containing `u` and the matching parameter on its own domain.
+    fn locate(&self, u: f64) -> (&Shape, f64) {
+        let mut offset = 0.0;
+        for (i, segment) in self.segments.iter().enumerate() {
+            let (start, end) = segment.domain();
+            if u < offset + end - start || i == self.segments.len() - 1 {
+                return (segment, start + (u - offset).clamp(0.0, end - start));
+            }
+            offset += end - start;
+        }
+        unreachable!()
+    }
+}
+
+impl Curve for Path {
+    fn domain(&self) -> (f64, f64) {
+        let length = self
+            .segments
+            .iter()
+            .map(|segment| {
+                let (start, end) = segment.domain();
+                end - start
+            })
+            .sum();
+        (0.0, length)
+    }
+
+    fn point(&self, u: f64) -> Vec<f64> {
+        let (segment, u) = self.locate(u);
+        segment.point(u)
+    }
+
+    fn derivative(&self, u: f64, k: usize) -> Vec<f64> {
+        let (segment, u) = self.locate(u);
+        segment.derivative(u, k)
+    }
+}
+
+// Affinely maps the knots so that `domain` becomes `start..end`. Derivatives of the remapped curve
+// pick up the factor of the chain rule through the knot differences.
+fn remap_knots(knots: &[f64], domain: (f64, f64), start: f64, end: f64) -> Vec<f64> {
+    let scale = (end - start) / (domain.1 - domain.0);
+    knots
+        .iter()
+        .map(|&t| start + (t - domain.0) * scale)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vector;
+
+    fn closed() -> ClosedBSplineCurve {
+        ClosedBSplineCurve::new(
+            vec![
+                vec![0.0, 0.0, 0.0],
+                vec![2.0, 0.0, 0.0],
+                vec![3.0, 2.0, 0.0],
+                vec![1.0, 3.0, 0.0],
+                vec![-1.0, 1.0, 0.0],
+            ],
+            4,
+        )
+    }
+
+    #[test]
+    fn closed_curves_are_smooth_at_the_seam() {
+        let curve = closed();
+        let (start, end) = curve.domain();
+        for k in 0..=2 {
+            let before = curve.derivative(end - 1e-9, k);
+            let after = curve.derivative(start, k);
+            assert!(vector::distance(&before, &after) < 1e-6);
+        }
+        assert!(vector::distance(&curve.point(end), &curve.point(start)) < 1e-12);
+    }
+
+    #[test]
+    fn open_closed_curves_keep_their_shape() {
+        let curve = closed();
+        let open = curve.open();
+        assert_eq!(open.domain(), curve.domain());
+        for i in 0..50 {
+            let u = i as f64 / 10.0;
+            assert!(vector::distance(&open.point(u), &curve.point(u)) < 1e-9);
+        }
+        assert!(vector::distance(&open.point(5.0), &curve.point(0.0)) < 1e-9);
+    }
+
+    fn bspline() -> BSplineCurve {
+        BSplineCurve::with_knots(
+            vec![
+                vec![0.0, 0.0, 0.0],
+                vec![1.0, 2.0, 0.0],
+                vec![3.0, 3.0, 1.0],
+                vec![4.0, 1.0, 2.0],
+                vec![6.0, 0.0, 1.0],
+                vec![7.0, 2.0, 0.0],
+            ],
+            4,
+            vec![0.0, 0.0, 0.0, 0.0, 0.5, 2.0, 3.0, 3.0, 3.0, 3.0],
+        )
+    }
+
+    #[test]
+    fn reversed_curves_run_backwards() {
+        let bezier = BezierCurve::new(bspline().control_points);
+        let reversed = bezier.reverse();
+        for i in 0..=10 {
+            let u = i as f64 / 10.0;
+            assert!(vector::distance(&reversed.point(1.0 - u), &bezier.point(u)) < 1e-12);
+        }
+
+        // On [0, 1], the reversed curve at 1 - u is the curve at u, with the odd derivatives negated.
+        let curve = bspline().with_domain(0.0, 1.0);
+        let reversed = curve.reverse();
+        assert_eq!(reversed.domain(), curve.domain());
+        for i in 1..10 {
+            let u = i as f64 / 10.0;
+            for k in 0..3 {
+                let sign = if k % 2 == 0 { 1.0 } else { -1.0 };
+                let expected = vector::scale(&curve.derivative(u, k), sign);
+                assert!(vector::distance(&reversed.derivative(1.0 - u, k), &expected) < 1e-9);
+            }
+        }
+    }
+
+    #[test]
+    fn affine_reparametrization_keeps_the_points() {
+        let curve = bspline();
+        let remapped = curve.with_domain(-1.0, 5.0);
+        assert_eq!(remapped.domain(), (-1.0, 5.0));
+        // The domain doubles, so every derivative of order k shrinks by 2^k.
+        for i in 0..10 {
+            let u = 0.3 * i as f64;
+            for k in 0..3 {
+                let expected = vector::scale(&curve.derivative(u, k), 0.5f64.powi(k as i32));
+                let remapped = remapped.derivative(2.0 * u - 1.0, k);
+                assert!(vector::distance(&remapped, &expected) < 1e-9);
+            }
+        }
+
+        let nurbs = NurbsCurve::with_knots(
+            curve.control_points.clone(),
+            vec![1.0, 2.0, 0.5, 1.0, 3.0, 1.0],
+            curve.degree,
+            curve.knots.clone(),
+        );
+        let remapped = nurbs.with_domain(0.0, 1.0);
+        for i in 0..10 {
+            let u = i as f64 / 10.0;
+            assert!(vector::distance(&remapped.point(u), &nurbs.point(3.0 * u)) < 1e-9);
+        }
+    }
+}
@@ -0,0 +1,175 @@
+use crate::curve::Curve;
+use crate::vector;
+
+pub struct FrenetFrame {
+    pub tangent: Vec<f64>,
+    pub normal: Vec<f64>,
+    pub binormal: Vec<f64>,
+}
+
+pub fn tangent(curve: &impl Curve, u: f64) -> Vec<f64> {
+    vector::normalize(&curve.derivative(u, 1))
+}
+
+pub fn curvature(curve: &impl Curve, u: f64) -> f64 {
+    let d1 = curve.derivative(u, 1);
+    let d2 = curve.derivative(u, 2);
+
+    vector::norm(&vector::cross(&d1, &d2)) / vector::norm(&d1).powi(3)
+}
+
+pub fn radius_of_curvature(curve: &impl Curve, u: f64) -> f64 {
+    1.0 / curvature(curve, u)
+}
+
+pub fn torsion(curve: &impl Curve, u: f64) -> f64 {
+    let d1 = curve.derivative(u, 1);
+    let d2 = curve.derivative(u, 2);
+    let d3 = curve.derivative(u, 3);
+    let d1_cross_d2 = vector::cross(&d1, &d2);
+
+    vector::dot(&d1_cross_d2, &d3) / vector::dot(&d1_cross_d2, &d1_cross_d2)
+}
+
+// The normal is undefined where the curve is locally straight, so no frame is returned there.
+pub fn frenet_frame(curve: &impl Curve, u: f64) -> Option<FrenetFrame> {
+    let d1 = curve.derivative(u, 1);
+    let d2 = curve.derivative(u, 2);
+    let d1_cross_d2 = vector::cross(&d1, &d2);
+    if vector::norm(&d1_cross_d2) <= f64::EPSILON * vector::norm(&d1).powi(3) {
+        return None;
+    }
+
+    let tangent = vector::normalize(&d1);
+    let binormal = vector::normalize(&d1_cross_d2);
+    let normal = vector::cross(&binormal, &tangent);
+
+    Some(FrenetFrame {
+        tangent,
+        normal,
+        binormal,
+    })
+}
+
+pub fn sample_parameters(curve: &impl Curve, samples: usize) -> Vec<f64> {
+    let (start, end) = curve.domain();
+    (0..samples)
+        .map(|j| start + (end - start) * j as f64 / (samples - 1) as f64)
+        .collect()
+}
+
+// Spikes go from the curve against the principal normal, with length proportional to the curvature,
+// so they switch sides at inflections.
+pub fn curvature_comb(curve: &impl Curve, samples: usize, scale: f64) -> Vec<(Vec<f64>, Vec<f64>)> {
+    sample_parameters(curve, samples)
+        .into_iter()
+        .map(|u| {
+            let point = curve.point(u);
+            let tip = match frenet_frame(curve, u) {
+                Some(frame) => vector::sub(
+                    &point,
+                    &vector::scale(&frame.normal, scale * curvature(curve, u)),
+                ),
+                None => point.clone(),
+            };
+            (point, tip)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curve::{BSplineCurve, BezierCurve, NurbsCurve};
+    use std::f64::consts::FRAC_1_SQRT_2;
+
+    // The quarter of the circle of radius 2 around the origin, in the xy plane.
+    fn quarter_circle() -> NurbsCurve {
+        NurbsCurve::with_knots(
+            vec![
+                vec![2.0, 0.0, 0.0],
+                vec![2.0, 2.0, 0.0],
+                vec![0.0, 2.0, 0.0],
+            ],
+            vec![1.0, FRAC_1_SQRT_2, 1.0],
+            3,
+            vec![0.0, 0.0, 0.0, 1.0, 1.0, 1.0],
+        )
+    }
+
+    // The twisted cubic (t, t², t³) for t in [0, 1].
+    fn twisted_cubic() -> Vec<Vec<f64>> {
+        vec![
+            vec![0.0, 0.0, 0.0],
+            vec![1.0 / 3.0, 0.0, 0.0],
+            vec![2.0 / 3.0, 1.0 / 3.0, 0.0],
+            vec![1.0, 1.0, 1.0],
+        ]
+    }
+
+    const PARAMETERS: [f64; 5] = [0.0, 0.2, 0.5, 0.75, 0.95];
+
+    #[test]
+    fn circle_has_the_curvature_of_its_radius() {
+        let circle = quarter_circle();
+        for u in PARAMETERS {
+            assert!((curvature(&circle, u) - 0.5).abs() < 1e-9);
+            assert!((radius_of_curvature(&circle, u) - 2.0).abs() < 1e-9);
+            assert!(torsion(&circle, u).abs() < 1e-9);
+        }
+    }
+
+    // With C' = (1, 2t, 3t²), C'' = (0, 2, 6t) and C''' = (0, 0, 6), C' x C'' = (6t², -6t, 2), so
+    // κ = √(36t⁴ + 36t² + 4) / (1 + 4t² + 9t⁴)^1.5 and τ = 3 / (9t⁴ + 9t² + 1).
+    fn assert_twisted_cubic(curve: &impl Curve) {
+        for t in PARAMETERS {
+            let t2 = t * t;
+            let expected_curvature = (36.0 * t2 * t2 + 36.0 * t2 + 4.0).sqrt()
+                / (1.0 + 4.0 * t2 + 9.0 * t2 * t2).powf(1.5);
+            let expected_torsion = 3.0 / (9.0 * t2 * t2 + 9.0 * t2 + 1.0);
+            assert!((curvature(curve, t) - expected_curvature).abs() < 1e-9);
+            assert!((torsion(curve, t) - expected_torsion).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn curvature_and_torsion_of_the_twisted_cubic() {
+        assert_twisted_cubic(&BezierCurve::new(twisted_cubic()));
+        assert_twisted_cubic(&BSplineCurve::new(twisted_cubic(), 4));
+    }
+
+    #[test]
+    fn frenet_frame_is_orthonormal() {
+        let curve = BezierCurve::new(twisted_cubic());
+        for t in PARAMETERS {
+            let frame = frenet_frame(&curve, t).unwrap();
+            let axes = [&frame.tangent, &frame.normal, &frame.binormal];
+            for (i, a) in axes.iter().enumerate() {
+                for (j, b) in axes.iter().enumerate() {
+                    let expected = if i == j { 1.0 } else { 0.0 };
+                    assert!((vector::dot(a, b) - expected).abs() < 1e-12);
+                }
+            }
+            assert!(
+                vector::distance(
+                    &vector::cross(&frame.tangent, &frame.normal),
+                    &frame.binormal
+                ) < 1e-12
+            );
+            assert!(vector::distance(&frame.tangent, &tangent(&curve, t)) < 1e-12);
+        }
+
+        let line = BezierCurve::new(vec![vec![0.0, 0.0, 0.0], vec![1.0, 2.0, 3.0]]);
+        assert!(frenet_frame(&line, 0.5).is_none());
+    }
+
+    #[test]
+    fn comb_spikes_point_away_from_the_centre() {
+        let comb = curvature_comb(&quarter_circle(), 9, 2.0);
+        assert_eq!(comb.len(), 9);
+        for (point, tip) in comb {
+            assert!((vector::norm(&point) - 2.0).abs() < 1e-9);
+            assert!((vector::norm(&tip) - 3.0).abs() < 1e-9);
+        }
+    }
+}
@@ -0,0 +1,238 @@
+use crate::arc_length::{GAUSS_NODES, GAUSS_WEIGHTS};
+use crate::{bezier, bspline, linalg, vector};
+#[cfg(feature = "serde")]
+use serde::Deserialize;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum Energy {
+    // ∫|C''|², the bending energy of a thin elastic beam.
+    Bending,
+    // ∫|C'''|², which penalises changes in curvature.
+    CurvatureVariation,
+}
+
+impl Energy {
+    fn order(self) -> usize {
+        match self {
+            Energy::Bending => 2,
+            Energy::CurvatureVariation => 3,
+        }
+    }
+}
+
+// Indices of the control points a fairing must keep so a join of the given continuity survives:
+// the first `continuity + 1` points, which carry the derivatives at the joint, and the last one.
+pub fn join_constraints(count: usize, continuity: usize) -> Vec<usize> {
+    let mut fixed: Vec<usize> = (0..=continuity.min(count - 1)).collect();
+    if !fixed.contains(&(count - 1)) {
+        fixed.push(count - 1);
+    }
+    fixed
+}
+
+// Gram matrix G_ij = ∫ B_i^(r) B_j^(r) over the given spans, with two Gauss-Legendre rules per span.
+fn gram_matrix(
+    count: usize,
+    spans: &[(f64, f64)],
+    derivative: impl Fn(f64, usize) -> f64,
+) -> Vec<Vec<f64>> {
+    let mut gram = vec![vec![0.0; count]; count];
+
+    for &(start, end) in spans {
+        for half in 0..2 {
+            let (a, b) = if half == 0 {
+                (start, 0.5 * (start + end))
+            } else {
+                (0.5 * (start + end), end)
+            };
+            for (node, weight) in GAUSS_NODES.iter().zip(GAUSS_WEIGHTS) {
+                let u = 0.5 * (a + b) + 0.5 * (b - a) * node;
+                let values: Vec<f64> = (0..count).map(|i| derivative(u, i)).collect();
+                let factor = 0.5 * (b - a) * weight;
+                for i in 0..count {
+                    for j in 0..count {
+                        gram[i][j] += factor * values[i] * values[j];
+                    }
+                }
+            }
+        }
+    }
+
+    gram
+}
+
+// Minimises |P - P₀|² + strength · E(P) over the free control points, which is linear:
+// (I + strength G_ff) P_f = P₀_f - strength G_fc P_c. G is positive semi-definite, so the system
+// is positive definite for any strength that is not negative.
+fn fair(
+    control_points: &[Vec<f64>],
+    fixed: &[usize],
+    strength: f64,
+    gram: &[Vec<f64>],
+) -> Vec<Vec<f64>> {
+    let free: Vec<usize> = (0..control_points.len())
+        .filter(|i| !fixed.contains(i))
+        .collect();
+
+    let mut matrix = vec![vec![0.0; free.len()]; free.len()];
+    let mut rhs = Vec::with_capacity(free.len());
+    for (a, &i) in free.iter().enumerate() {
+        matrix[a][a] = 1.0;
+        for (b, &j) in free.iter().enumerate() {
+            matrix[a][b] += strength * gram[i][j];
+        }
+
+        let mut row = control_points[i].clone();
+        for &j in fixed {
+            row = vector::sub(
+                &row,
+                &vector::scale(&control_points[j], strength * gram[i][j]),
+            );
+        }
+        rhs.push(row);
+    }
+
+    let solution = linalg::solve(matrix, rhs).expect("the fairing system is positive definite");
+    let mut faired = control_points.to_vec();
+    for (a, &i) in free.iter().enumerate() {
+        faired[i] = solution[a].clone();
+    }
+    faired
+}
+
+pub fn is_valid_strength(strength: f64) -> bool {
+    strength >= 0.0 && strength.is_finite()
+}
+
+fn assert_valid_strength(strength: f64) {
+    assert!(
+        is_valid_strength(strength),
+        "the fairing strength must be a finite number, at least 0, not {}",
+        strength
+    );
+}
+
+pub fn fair_bezier(
+    control_points: &[Vec<f64>],
+    fixed: &[usize],
+    energy: Energy,
+    strength: f64,
+) -> Vec<Vec<f64>> {
+    assert_valid_strength(strength);
+    let n = control_points.len() as u128 - 1;
+    let gram = gram_matrix(control_points.len(), &[(0.0, 1.0)], |u, i| {
+        bezier::dk_bezier(u, i as i128, n, energy.order())
+    });
+
+    fair(control_points, fixed, strength, &gram)
+}
+
+pub fn fair_bspline(
+    control_points: &[Vec<f64>],
+    degree: usize,
+    knots: &[f64],
+    fixed: &[usize],
+    energy: Energy,
+    strength: f64,
+) -> Vec<Vec<f64>> {
+    assert_valid_strength(strength);
+    let spans: Vec<(f64, f64)> = knots[degree - 1..=control_points.len()]
+        .windows(2)
+        .filter(|span| span[0] < span[1])
+        .map(|span| (span[0], span[1]))
+        .collect();
+    let gram = gram_matrix(control_points.len(), &spans, |u, i| {
+        bspline::dk_bspline(u, i, degree, knots, energy.order())
+    });
+
+    fair(control_points, fixed, strength, &gram)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wiggly(count: usize) -> Vec<Vec<f64>> {
+        (0..count)
+            .map(|i| {
+                let x = i as f64;
+                vec![x, if i % 2 == 0 { 1.0 } else { -1.0 }, 0.1 * x * x]
+            })
+            .collect()
+    }
+
+    // E(P) = Σ_ij G_ij P_i · P_j.
+    fn energy(control_points: &[Vec<f64>], gram: &[Vec<f64>]) -> f64 {
+        let mut energy = 0.0;
+        for (i, p) in control_points.iter().enumerate() {
+            for (j, q) in control_points.iter().enumerate() {
+                energy += gram[i][j] * vector::dot(p, q);
+            }
+        }
+        energy
+    }
+
+    #[test]
+    fn fairing_a_bezier_curve_lowers_its_energy() {
+        let control_points = wiggly(7);
+        let n = control_points.len() as u128 - 1;
+        for (energy_kind, continuity) in [(Energy::Bending, 1), (Energy::CurvatureVariation, 2)] {
+            let fixed = join_constraints(control_points.len(), continuity);
+            let gram = gram_matrix(control_points.len(), &[(0.0, 1.0)], |u, i| {
+                bezier::dk_bezier(u, i as i128, n, energy_kind.order())
+            });
+
+            let faired = fair_bezier(&control_points, &fixed, energy_kind, 0.5);
+            assert!(energy(&faired, &gram) < energy(&control_points, &gram));
+            for &i in &fixed {
+                assert_eq!(faired[i], control_points[i]);
+            }
+        }
+    }
+
+    #[test]
+    fn fairing_a_bspline_lowers_its_energy() {
+        let control_points = wiggly(9);
+        let degree = 4;
+        let knots = bspline::generate_knot_vector(control_points.len() - 1, degree);
+        let spans: Vec<(f64, f64)> = knots
+            .windows(2)
+            .filter(|span| span[0] < span[1])
+            .map(|span| (span[0], span[1]))
+            .collect();
+        let fixed = join_constraints(control_points.len(), 2);
+
+        let mut previous = f64::INFINITY;
+        for strength in [0.0, 0.1, 1.0, 10.0] {
+            let gram = gram_matrix(control_points.len(), &spans, |u, i| {
+                bspline::dk_bspline(u, i, degree, &knots, 2)
+            });
+            let faired = fair_bspline(
+                &control_points,
+                degree,
+                &knots,
+                &fixed,
+                Energy::Bending,
+                strength,
+            );
+            // A stronger fairing never leaves more energy behind.
+            let faired_energy = energy(&faired, &gram);
+            assert!(faired_energy <= previous * (1.0 + 1e-12));
+            previous = faired_energy;
+            for &i in &fixed {
+                assert_eq!(faired[i], control_points[i]);
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "the fairing strength must be a finite number")]
+    fn negative_strength_is_rejected() {
+        fair_bezier(&wiggly(5), &[0, 4], Energy::Bending, -1.0);
+    }
+}
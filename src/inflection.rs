@@ -0,0 +1,155 @@
+use crate::curve::Curve;
+use crate::differential::sample_parameters;
+use crate::vector;
+
+const SAMPLES: usize = 1000;
+const MAX_ITERATIONS: usize = 60;
+const CURVATURE_TOLERANCE: f64 = 1e-9;
+
+// Halves [start, end] keeping the half where `sign` changes, `sign(start)` and `sign(end)` must
+// differ.
+fn bisect(sign: impl Fn(f64) -> bool, mut start: f64, mut end: f64) -> f64 {
+    let start_sign = sign(start);
+    for _ in 0..MAX_ITERATIONS {
+        let middle = 0.5 * (start + end);
+        if sign(middle) == start_sign {
+            start = middle;
+        } else {
+            end = middle;
+        }
+    }
+    0.5 * (start + end)
+}
+
+// Golden-section search for the minimum of `f` in [start, end].
+fn minimize(f: impl Fn(f64) -> f64, mut start: f64, mut end: f64) -> f64 {
+    let ratio = (5.0_f64.sqrt() - 1.0) / 2.0;
+    for _ in 0..MAX_ITERATIONS {
+        let left = end - ratio * (end - start);
+        let right = start + ratio * (end - start);
+        if f(left) < f(right) {
+            end = right;
+        } else {
+            start = left;
+        }
+    }
+    0.5 * (start + end)
+}
+
+fn binormal_direction(curve: &impl Curve, u: f64) -> Vec<f64> {
+    vector::cross(&curve.derivative(u, 1), &curve.derivative(u, 2))
+}
+
+// Parameters where the curvature vanishes. C' x C'' flips direction at the inflections of a planar
+// curve, while in space it only touches zero, so both sign changes and near-zero minima are kept.
+pub fn inflections(curve: &impl Curve) -> Vec<f64> {
+    let parameters = sample_parameters(curve, SAMPLES);
+    let directions: Vec<Vec<f64>> = parameters
+        .iter()
+        .map(|&u| binormal_direction(curve, u))
+        .collect();
+    let magnitude = |u: f64| vector::norm(&binormal_direction(curve, u));
+    let is_flat = |u: f64| {
+        magnitude(u) <= CURVATURE_TOLERANCE * vector::norm(&curve.derivative(u, 1)).powi(3)
+    };
+
+    let mut inflections = Vec::new();
+    for i in 1..parameters.len() {
+        let (start, end) = (parameters[i - 1], parameters[i]);
+        let reference = &directions[i - 1];
+
+        if vector::dot(reference, &directions[i]) < 0.0 {
+            let u = bisect(
+                |u| vector::dot(reference, &binormal_direction(curve, u)) > 0.0,
+                start,
+                end,
+            );
+            inflections.push(u);
+        } else if i + 1 < parameters.len() {
+            // A local minimum of |C' x C''| around this sample may touch zero.
+            let (before, here, after) = (
+                vector::norm(&directions[i - 1]),
+                vector::norm(&directions[i]),
+                vector::norm(&directions[i + 1]),
+            );
+            if here <= before && here <= after {
+                let u = minimize(magnitude, start, parameters[i + 1]);
+                if is_flat(u) {
+                    inflections.push(u);
+                }
+            }
+        }
+    }
+
+    inflections.dedup_by(|a, b| (*a - *b).abs() <= 1e-9);
+    inflections
+}
+
+// Parameters where the given coordinate reaches a local minimum or maximum.
+pub fn extrema(curve: &impl Curve, coordinate: usize) -> Vec<f64> {
+    let parameters = sample_parameters(curve, SAMPLES);
+    let slope = |u: f64| curve.derivative(u, 1)[coordinate];
+
+    let mut extrema = Vec::new();
+    let mut previous = slope(parameters[0]);
+    for i in 1..parameters.len() {
+        let current = slope(parameters[i]);
+        if previous != 0.0 && (current == 0.0 || previous.signum() != current.signum()) {
+            let u = if current == 0.0 {
+                parameters[i]
+            } else {
+                bisect(|u| slope(u) > 0.0, parameters[i - 1], parameters[i])
+            };
+            extrema.push(u);
+        }
+        previous = current;
+    }
+
+    extrema
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curve::BezierCurve;
+
+    // x = 3t and y = 3t(1 - t)(1 - 2t), an S whose only inflection is at t = 1/2.
+    fn s_curve() -> BezierCurve {
+        BezierCurve::new(vec![
+            vec![0.0, 0.0, 0.0],
+            vec![1.0, 1.0, 0.0],
+            vec![2.0, -1.0, 0.0],
+            vec![3.0, 0.0, 0.0],
+        ])
+    }
+
+    #[test]
+    fn inflection_of_an_s_curve() {
+        let parameters = inflections(&s_curve());
+        assert_eq!(parameters.len(), 1);
+        assert!((parameters[0] - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn convex_curve_has_no_inflections() {
+        let curve = BezierCurve::new(vec![
+            vec![0.0, 0.0, 0.0],
+            vec![1.0, 0.0, 0.0],
+            vec![2.0, 1.0, 0.0],
+            vec![2.0, 2.0, 0.0],
+        ]);
+        assert!(inflections(&curve).is_empty());
+    }
+
+    #[test]
+    fn extrema_of_an_s_curve() {
+        // y' = 3 - 18t + 18t² vanishes at t = 1/2 ± √3/6, while x keeps growing.
+        let curve = s_curve();
+        let parameters = extrema(&curve, 1);
+        assert_eq!(parameters.len(), 2);
+        for (u, expected) in parameters.iter().zip([-1.0, 1.0]) {
+            assert!((u - (0.5 + expected * 3.0_f64.sqrt() / 6.0)).abs() < 1e-9);
+        }
+        assert!(extrema(&curve, 0).is_empty());
+    }
+}
@@ -0,0 +1,252 @@
+use crate::bspline;
+use crate::curve::BSplineCurve;
+use crate::{linalg, vector};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Parametrization {
+    Uniform,
+    ChordLength,
+    Centripetal,
+}
+
+// Drops every point equal to the one before it. A repeated point gets the same chord-length or
+// centripetal parameter as its copy, which would leave two equal rows in the interpolation system.
+fn distinct_points(points: &[Vec<f64>]) -> Vec<Vec<f64>> {
+    let mut distinct: Vec<Vec<f64>> = Vec::with_capacity(points.len());
+    for point in points {
+        if distinct.last() != Some(point) {
+            distinct.push(point.clone());
+        }
+    }
+    distinct
+}
+
+// Parameters in [0, 1] assigned to each data point.
+pub fn parameters(points: &[Vec<f64>], parametrization: Parametrization) -> Vec<f64> {
+    let steps: Vec<f64> = points
+        .windows(2)
+        .map(|pair| {
+            let chord = vector::distance(&pair[0], &pair[1]);
+            match parametrization {
+                Parametrization::Uniform => 1.0,
+                Parametrization::ChordLength => chord,
+                Parametrization::Centripetal => chord.sqrt(),
+            }
+        })
+        .collect();
+    let total: f64 = steps.iter().sum();
+
+    let mut parameters = Vec::with_capacity(points.len());
+    parameters.push(0.0);
+    for step in steps {
+        parameters.push(parameters[parameters.len() - 1] + step / total);
+    }
+    let last = parameters.len() - 1;
+    parameters[last] = 1.0;
+    parameters
+}
+
+// Knots averaged over windows of p consecutive parameters (Piegl & Tiller eq. 9.8). Each
+// constrained end derivative adds one control point, and with it the window next to that end.
+fn averaged_knots(
+    parameters: &[f64],
+    degree: usize,
+    constrained_start: bool,
+    constrained_end: bool,
+) -> Vec<f64> {
+    let p = degree - 1;
+    let n = parameters.len() - 1;
+    let first = if constrained_start { 0 } else { 1 };
+    let last = if constrained_end { n + 1 - p } else { n - p };
+
+    let mut knots = vec![0.0; degree];
+    for j in first..=last {
+        knots.push(parameters[j..j + p].iter().sum::<f64>() / p as f64);
+    }
+    knots.extend(std::iter::repeat_n(1.0, degree));
+    knots
+}
+
+pub fn interpolate(
+    points: &[Vec<f64>],
+    degree: usize,
+    parametrization: Parametrization,
+) -> BSplineCurve {
+    interpolate_with_derivatives(points, degree, parametrization, None, None)
+}
+
+// Global interpolation of `points`, optionally matching the first derivatives at the ends, which
+// are taken with respect to the [0, 1] parameter of the resulting curve. Repeated consecutive points
+// are interpolated once.
+pub fn interpolate_with_derivatives(
+    points: &[Vec<f64>],
+    degree: usize,
+    parametrization: Parametrization,
+    start_derivative: Option<&[f64]>,
+    end_derivative: Option<&[f64]>,
+) -> BSplineCurve {
+    let points = distinct_points(points);
+    interpolate_at_parameters(
+        &points,
+        degree,
+        &parameters(&points, parametrization),
+        start_derivative,
+        end_derivative,
+    )
+}
+
+// Interpolation at given parameters in [0, 1], so point sets that share them also share the knots.
+pub fn interpolate_at(points: &[Vec<f64>], degree: usize, parameters: &[f64]) -> BSplineCurve {
+    interpolate_at_parameters(points, degree, parameters, None, None)
+}
+
+fn interpolate_at_parameters(
+    points: &[Vec<f64>],
+    degree: usize,
+    parameters: &[f64],
+    start_derivative: Option<&[f64]>,
+    end_derivative: Option<&[f64]>,
+) -> BSplineCurve {
+    let p = degree - 1;
+    let n = points.len() - 1;
+    assert!(
+        n >= p,
+        "{} distinct points cannot define a curve of order {}",
+        n + 1,
+        degree
+    );
+
+    let knots = averaged_knots(
+        parameters,
+        degree,
+        start_derivative.is_some(),
+        end_derivative.is_some(),
+    );
+    let count = knots.len() - degree;
+    let m = knots.len() - 1;
+
+    let mut matrix = Vec::with_capacity(count);
+    let mut rhs = Vec::with_capacity(count);
+    let unit_row = |i: usize| {
+        let mut row = vec![0.0; count];
+        row[i] = 1.0;
+        row
+    };
+
+    matrix.push(unit_row(0));
+    rhs.push(points[0].clone());
+    if let Some(derivative) = start_derivative {
+        // C'(0) = p / u_{p+1} (P_1 - P_0)
+        let mut row = vec![0.0; count];
+        row[0] = -1.0;
+        row[1] = 1.0;
+        matrix.push(row);
+        rhs.push(vector::scale(derivative, knots[p + 1] / p as f64));
+    }
+    for k in 1..n {
+        matrix.push(
+            (0..count)
+                .map(|i| bspline::basis_function(parameters[k], i, degree, &knots))
+                .collect(),
+        );
+        rhs.push(points[k].clone());
+    }
+    if let Some(derivative) = end_derivative {
+        // C'(1) = p / (1 - u_{m-p-1}) (P_{count-1} - P_{count-2})
+        let mut row = vec![0.0; count];
+        row[count - 2] = -1.0;
+        row[count - 1] = 1.0;
+        matrix.push(row);
+        rhs.push(vector::scale(
+            derivative,
+            (1.0 - knots[m - p - 1]) / p as f64,
+        ));
+    }
+    // The basis functions vanish at the very end of the domain, but the curve is clamped there.
+    matrix.push(unit_row(count - 1));
+    rhs.push(points[n].clone());
+
+    let control_points =
+        linalg::solve(matrix, rhs).expect("interpolation parameters must be distinct");
+    BSplineCurve::with_knots(control_points, degree, knots)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curve::Curve;
+
+    fn points() -> Vec<Vec<f64>> {
+        vec![
+            vec![0.0, 0.0, 0.0],
+            vec![3.0, 4.0, 0.0],
+            vec![-1.0, 4.0, 0.0],
+            vec![-4.0, 0.0, 0.0],
+            vec![-4.0, -3.0, 0.0],
+        ]
+    }
+
+    #[test]
+    fn chord_length_parameters() {
+        // The chords are 5, 4, 5 and 3 long.
+        let parameters = parameters(&points(), Parametrization::ChordLength);
+        let expected = [0.0, 5.0 / 17.0, 9.0 / 17.0, 14.0 / 17.0, 1.0];
+        for (u, expected) in parameters.iter().zip(expected) {
+            assert!((u - expected).abs() < 1e-12);
+        }
+    }
+
+    // Evaluation stops just short of the end of the domain, hence the looser tolerance.
+    #[test]
+    fn passes_through_the_points() {
+        for parametrization in [
+            Parametrization::Uniform,
+            Parametrization::ChordLength,
+            Parametrization::Centripetal,
+        ] {
+            let points = points();
+            let curve = interpolate(&points, 4, parametrization);
+            for (point, u) in points
+                .iter()
+                .zip(super::parameters(&points, parametrization))
+            {
+                assert!(vector::distance(point, &curve.point(u)) < 1e-8);
+            }
+        }
+    }
+
+    #[test]
+    fn matches_the_end_derivatives() {
+        let (start, end) = ([1.0, 10.0, 0.0], [-2.0, -6.0, 0.0]);
+        let points = points();
+        let curve = interpolate_with_derivatives(
+            &points,
+            4,
+            Parametrization::Centripetal,
+            Some(&start),
+            Some(&end),
+        );
+
+        assert_eq!(curve.control_points.len(), points.len() + 2);
+        assert!(vector::distance(&curve.derivative(0.0, 1), &start) < 1e-6);
+        assert!(vector::distance(&curve.derivative(1.0, 1), &end) < 1e-6);
+        for (point, u) in points
+            .iter()
+            .zip(parameters(&points, Parametrization::Centripetal))
+        {
+            assert!(vector::distance(point, &curve.point(u)) < 1e-8);
+        }
+    }
+
+    #[test]
+    fn repeated_points_are_interpolated_once() {
+        let mut repeated = points();
+        repeated.insert(2, repeated[1].clone());
+        repeated.push(repeated[repeated.len() - 1].clone());
+        let curve = interpolate(&repeated, 4, Parametrization::ChordLength);
+        assert_eq!(
+            curve,
+            interpolate(&points(), 4, Parametrization::ChordLength)
+        );
+    }
+}
@@ -0,0 +1,339 @@
+use crate::bounds::control_box;
+use crate::curve::Curve;
+use crate::{bezier, bspline, vector};
+
+const MAX_DEPTH: usize = 48;
+const MAX_ITERATIONS: usize = 20;
+const SIZE_TOLERANCE: f64 = 1e-6;
+const TOLERANCE: f64 = 1e-8;
+const CLUSTER_SIZE: f64 = 1e-3;
+
+// Bézier control points together with the parameter range they cover on the whole curve.
+pub type Piece = (Vec<Vec<f64>>, (f64, f64));
+
+#[derive(Clone, Debug)]
+pub struct Intersection {
+    pub first_parameter: f64,
+    pub second_parameter: f64,
+    pub point: Vec<f64>,
+}
+
+pub fn bezier_pieces(control_points: &[Vec<f64>]) -> Vec<Piece> {
+    vec![(control_points.to_vec(), (0.0, 1.0))]
+}
+
+pub fn bspline_pieces(control_points: &[Vec<f64>], degree: usize, knots: &[f64]) -> Vec<Piece> {
+    let mut spans: Vec<f64> = knots[degree - 1..=control_points.len()].to_vec();
+    spans.dedup();
+
+    bspline::to_bezier_segments(control_points, degree, knots)
+        .into_iter()
+        .zip(spans.windows(2).map(|span| (span[0], span[1])))
+        .collect()
+}
+
+fn split_piece((control_points, (start, end)): &Piece) -> (Piece, Piece) {
+    let middle = 0.5 * (start + end);
+    let (left, right) = bezier::split(control_points, 0.5);
+    ((left, (*start, middle)), (right, (middle, *end)))
+}
+
+// Keeps halving the piece with the larger bounding box while the boxes overlap.
+fn subdivide(first: &Piece, second: &Piece, depth: usize, candidates: &mut Vec<(f64, f64)>) {
+    if !control_box(&first.0).overlaps(&control_box(&second.0), TOLERANCE) {
+        return;
+    }
+
+    let (first_size, second_size) = (
+        control_box(&first.0).diagonal(),
+        control_box(&second.0).diagonal(),
+    );
+    if depth == MAX_DEPTH || first_size.max(second_size) <= SIZE_TOLERANCE {
+        let (first_start, first_end) = first.1;
+        let (second_start, second_end) = second.1;
+        candidates.push((
+            0.5 * (first_start + first_end),
+            0.5 * (second_start + second_end),
+        ));
+        return;
+    }
+
+    if first_size >= second_size {
+        let (left, right) = split_piece(first);
+        subdivide(&left, second, depth + 1, candidates);
+        subdivide(&right, second, depth + 1, candidates);
+    } else {
+        let (left, right) = split_piece(second);
+        subdivide(first, &left, depth + 1, candidates);
+        subdivide(first, &right, depth + 1, candidates);
+    }
+}
+
+// Gauss-Newton iterations on F(s, t) = A(s) - B(t).
+fn refine(first: &impl Curve, second: &impl Curve, mut s: f64, mut t: f64) -> (f64, f64) {
+    let (first_start, first_end) = first.domain();
+    let (second_start, second_end) = second.domain();
+
+    for _ in 0..MAX_ITERATIONS {
+        let f = vector::sub(&first.point(s), &second.point(t));
+        if vector::norm(&f) <= TOLERANCE * TOLERANCE {
+            break;
+        }
+
+        let a = first.derivative(s, 1);
+        let b = second.derivative(t, 1);
+        let (aa, ab, bb) = (
+            vector::dot(&a, &a),
+            vector::dot(&a, &b),
+            vector::dot(&b, &b),
+        );
+        let (af, bf) = (vector::dot(&a, &f), vector::dot(&b, &f));
+        let determinant = aa * bb - ab * ab;
+        if determinant.abs() <= f64::EPSILON * aa * bb {
+            break;
+        }
+
+        s = (s - (bb * af - ab * bf) / determinant).clamp(first_start, first_end);
+        t = (t - (ab * af - aa * bf) / determinant).clamp(second_start, second_end);
+    }
+
+    (s, t)
+}
+
+fn collect_intersections(
+    first: &impl Curve,
+    second: &impl Curve,
+    candidates: Vec<(f64, f64)>,
+    intersections: &mut Vec<Intersection>,
+) {
+    // Tangential contacts leave long runs of neighbouring candidates, refining one of them is enough.
+    let (first_start, first_end) = first.domain();
+    let (second_start, second_end) = second.domain();
+    let mut seeds: Vec<(f64, f64)> = Vec::new();
+
+    for (s, t) in candidates {
+        let is_clustered = seeds.iter().any(|&(seed_s, seed_t)| {
+            (seed_s - s).abs() <= CLUSTER_SIZE * (first_end - first_start)
+                && (seed_t - t).abs() <= CLUSTER_SIZE * (second_end - second_start)
+        });
+        if is_clustered {
+            continue;
+        }
+        seeds.push((s, t));
+
+        let (s, t) = refine(first, second, s, t);
+        let point = first.point(s);
+        if vector::distance(&point, &second.point(t)) > TOLERANCE {
+            continue;
+        }
+        let is_duplicate = intersections.iter().any(|intersection| {
+            (intersection.first_parameter - s).abs() <= TOLERANCE.sqrt()
+                && (intersection.second_parameter - t).abs() <= TOLERANCE.sqrt()
+        });
+        if !is_duplicate {
+            intersections.push(Intersection {
+                first_parameter: s,
+                second_parameter: t,
+                point,
+            });
+        }
+    }
+}
+
+pub fn curve_intersections(
+    first: &impl Curve,
+    first_pieces: &[Piece],
+    second: &impl Curve,
+    second_pieces: &[Piece],
+) -> Vec<Intersection> {
+    let mut candidates = Vec::new();
+    for first_piece in first_pieces {
+        for second_piece in second_pieces {
+            subdivide(first_piece, second_piece, 0, &mut candidates);
+        }
+    }
+
+    let mut intersections = Vec::new();
+    collect_intersections(first, second, candidates, &mut intersections);
+    intersections.sort_by(|a, b| a.first_parameter.total_cmp(&b.first_parameter));
+    intersections
+}
+
+fn turning_angle(control_points: &[Vec<f64>]) -> f64 {
+    let edges: Vec<Vec<f64>> = control_points
+        .windows(2)
+        .map(|pair| vector::sub(&pair[1], &pair[0]))
+        .filter(|edge| vector::norm(edge) > 0.0)
+        .collect();
+
+    edges
+        .windows(2)
+        .map(|pair| {
+            let cosine =
+                vector::dot(&pair[0], &pair[1]) / (vector::norm(&pair[0]) * vector::norm(&pair[1]));
+            cosine.clamp(-1.0, 1.0).acos()
+        })
+        .sum()
+}
+
+// A piece whose control polygon turns by less than half a revolution cannot cross itself.
+fn simple_pieces(piece: &Piece, depth: usize, pieces: &mut Vec<Piece>) {
+    if depth == MAX_DEPTH || turning_angle(&piece.0) < std::f64::consts::PI {
+        pieces.push(piece.clone());
+        return;
+    }
+
+    let (left, right) = split_piece(piece);
+    simple_pieces(&left, depth + 1, pieces);
+    simple_pieces(&right, depth + 1, pieces);
+}
+
+pub fn self_intersections(curve: &impl Curve, pieces: &[Piece]) -> Vec<Intersection> {
+    let mut simple = Vec::new();
+    for piece in pieces {
+        simple_pieces(piece, 0, &mut simple);
+    }
+
+    let mut intersections = Vec::new();
+    for i in 0..simple.len() {
+        for j in i + 1..simple.len() {
+            let mut candidates = Vec::new();
+            subdivide(&simple[i], &simple[j], 0, &mut candidates);
+
+            // Consecutive pieces always meet where one ends and the next starts.
+            let shared = simple[i].1 .1;
+            if j == i + 1 {
+                candidates.retain(|&(s, t)| {
+                    (s - shared).abs() > TOLERANCE.sqrt() || (t - shared).abs() > TOLERANCE.sqrt()
+                });
+            }
+
+            let mut found = Vec::new();
+            collect_intersections(curve, curve, candidates, &mut found);
+            found.retain(|intersection| {
+                j != i + 1
+                    || (intersection.first_parameter - shared).abs() > TOLERANCE.sqrt()
+                    || (intersection.second_parameter - shared).abs() > TOLERANCE.sqrt()
+            });
+            for intersection in found {
+                let is_duplicate = intersections.iter().any(|other: &Intersection| {
+                    (other.first_parameter - intersection.first_parameter).abs() <= TOLERANCE.sqrt()
+                        && (other.second_parameter - intersection.second_parameter).abs()
+                            <= TOLERANCE.sqrt()
+                });
+                if !is_duplicate {
+                    intersections.push(intersection);
+                }
+            }
+        }
+    }
+
+    intersections.sort_by(|a, b| a.first_parameter.total_cmp(&b.first_parameter));
+    intersections
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curve::BezierCurve;
+
+    fn line(start: [f64; 2], end: [f64; 2]) -> BezierCurve {
+        BezierCurve::new(vec![
+            vec![start[0], start[1], 0.0],
+            vec![end[0], end[1], 0.0],
+        ])
+    }
+
+    #[test]
+    fn crossing_lines() {
+        let (first, second) = (line([0.0, 0.0], [2.0, 2.0]), line([0.0, 2.0], [2.0, 0.0]));
+        let intersections = curve_intersections(
+            &first,
+            &bezier_pieces(&first.control_points),
+            &second,
+            &bezier_pieces(&second.control_points),
+        );
+
+        assert_eq!(intersections.len(), 1);
+        assert!((intersections[0].first_parameter - 0.5).abs() < 1e-8);
+        assert!((intersections[0].second_parameter - 0.5).abs() < 1e-8);
+        assert!(vector::distance(&intersections[0].point, &[1.0, 1.0, 0.0]) < 1e-8);
+    }
+
+    #[test]
+    fn parabola_and_line() {
+        // y = 4t(1 - t) at x = 2t meets y = 3/4 at t = 1/4 and t = 3/4.
+        let parabola = BezierCurve::new(vec![
+            vec![0.0, 0.0, 0.0],
+            vec![1.0, 2.0, 0.0],
+            vec![2.0, 0.0, 0.0],
+        ]);
+        let chord = line([0.0, 0.75], [2.0, 0.75]);
+        let intersections = curve_intersections(
+            &parabola,
+            &bezier_pieces(&parabola.control_points),
+            &chord,
+            &bezier_pieces(&chord.control_points),
+        );
+
+        assert_eq!(intersections.len(), 2);
+        for (intersection, expected) in intersections.iter().zip([0.25, 0.75]) {
+            assert!((intersection.first_parameter - expected).abs() < 1e-8);
+            assert!((intersection.second_parameter - expected).abs() < 1e-8);
+        }
+    }
+
+    #[test]
+    fn disjoint_curves() {
+        let (first, second) = (line([0.0, 0.0], [1.0, 0.0]), line([0.0, 1.0], [1.0, 1.0]));
+        assert!(curve_intersections(
+            &first,
+            &bezier_pieces(&first.control_points),
+            &second,
+            &bezier_pieces(&second.control_points),
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn loop_of_a_cubic() {
+        // x = 14t³ - 21t² + 9t and y = 3t(1 - t) cross where x = 1 away from t = 1/2, that is at the
+        // roots (7 ± √21) / 14 of 7t² - 7t + 1.
+        let curve = BezierCurve::new(vec![
+            vec![0.0, 0.0, 0.0],
+            vec![3.0, 1.0, 0.0],
+            vec![-1.0, 1.0, 0.0],
+            vec![2.0, 0.0, 0.0],
+        ]);
+        let intersections = self_intersections(&curve, &bezier_pieces(&curve.control_points));
+
+        assert_eq!(intersections.len(), 1);
+        let (s, t) = (
+            intersections[0].first_parameter,
+            intersections[0].second_parameter,
+        );
+        let (low, high) = (
+            (7.0 - 21.0_f64.sqrt()) / 14.0,
+            (7.0 + 21.0_f64.sqrt()) / 14.0,
+        );
+        assert!((s.min(t) - low).abs() < 1e-8);
+        assert!((s.max(t) - high).abs() < 1e-8);
+    }
+
+    #[test]
+    fn bspline_pieces_cover_the_knot_spans() {
+        let control_points = vec![
+            vec![0.0, 0.0, 0.0],
+            vec![1.0, 1.0, 0.0],
+            vec![2.0, -1.0, 0.0],
+            vec![3.0, 1.0, 0.0],
+            vec![4.0, 0.0, 0.0],
+        ];
+        let knots = [0.0, 0.0, 0.0, 0.0, 0.5, 1.0, 1.0, 1.0, 1.0];
+        let spans: Vec<(f64, f64)> = bspline_pieces(&control_points, 4, &knots)
+            .into_iter()
+            .map(|(_, span)| span)
+            .collect();
+        assert_eq!(spans, vec![(0.0, 0.5), (0.5, 1.0)]);
+    }
+}
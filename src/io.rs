@@ -0,0 +1,494 @@
+use crate::bspline;
+use crate::curve::{BSplineCurve, BezierCurve, NurbsCurve};
+#[cfg(feature = "serde")]
+use serde::Deserialize;
+use std::fmt;
+use std::fs;
+
+// `line` and `column` are 1-based; both are 0 when the error concerns the file as a whole.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParseError {
+    pub file: String,
+    pub line: usize,
+    pub column: usize,
+    pub reason: String,
+}
+
+impl ParseError {
+    pub(crate) fn new(file: &str, line: usize, column: usize, reason: impl Into<String>) -> Self {
+        Self {
+            file: file.to_string(),
+            line,
+            column,
+            reason: reason.into(),
+        }
+    }
+
+    fn at(file: &str, position: Position, reason: impl Into<String>) -> Self {
+        Self::new(file, position.line, position.column, reason)
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}:{}: {}",
+            self.file, self.line, self.column, self.reason
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum CurveKind {
+    Bezier,
+    BSpline,
+    Nurbs,
+}
+
+impl fmt::Display for CurveKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CurveKind::Bezier => write!(f, "bezier"),
+            CurveKind::BSpline => write!(f, "bspline"),
+            CurveKind::Nurbs => write!(f, "nurbs"),
+        }
+    }
+}
+
+// The contents of an input file:
+//
+//     # Comments run from `#` to the end of the line.
+//     nurbs 2d                 optional header: curve type and/or dimension, 3d by default
+//     3                        the degree (the order for B-Splines and NURBS)
+//     knots 0, 0, 0, 1, 1, 1   optional explicit knot vector
+//     0.0, 1.0, 0.5            one control point per line, followed by its weight for NURBS
+//
+// Files without a header keep the original meaning: a degree followed by `x, y, z` lines.
+// Control points are always returned in 3D, with z = 0 for 2D files.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CurveFile {
+    pub kind: Option<CurveKind>,
+    pub dimension: usize,
+    pub degree: usize,
+    pub control_points: Vec<Vec<f64>>,
+    pub weights: Option<Vec<f64>>,
+    pub knots: Option<Vec<f64>>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Position {
+    line: usize,
+    column: usize,
+}
+
+// Where the parts of a file were read from, for the errors found while validating it.
+struct Positions {
+    header: Position,
+    degree: Position,
+    knots: Position,
+}
+
+// Splits `text` at commas, keeping the column where each trimmed field starts. `offset` is the
+// 0-based column of `text` within its line.
+fn fields(text: &str, offset: usize) -> Vec<(usize, &str)> {
+    let mut fields = Vec::new();
+    let mut start = offset;
+    for field in text.split(',') {
+        let leading = field.len() - field.trim_start().len();
+        fields.push((start + leading + 1, field.trim()));
+        start += field.len() + 1;
+    }
+    fields
+}
+
+fn numbers(file: &str, line: usize, fields: &[(usize, &str)]) -> Result<Vec<f64>, ParseError> {
+    fields
+        .iter()
+        .map(|&(column, field)| {
+            // `parse` also takes "nan" and "inf", which no coordinate, weight or knot can be.
+            field
+                .parse::<f64>()
+                .ok()
+                .filter(|number| number.is_finite())
+                .ok_or_else(|| {
+                    ParseError::new(
+                        file,
+                        line,
+                        column,
+                        format!("expected a finite number, found {field:?}"),
+                    )
+                })
+        })
+        .collect()
+}
+
+// Applies the header tokens, or returns `None` if the line is not a header.
+fn parse_header(
+    file: &str,
+    line: usize,
+    text: &str,
+    parsed: &mut CurveFile,
+) -> Result<Option<()>, ParseError> {
+    if text.trim().parse::<usize>().is_ok() {
+        return Ok(None);
+    }
+
+    let mut start = 0;
+    for token in text.split_whitespace() {
+        let column = start + text[start..].find(token).unwrap() + 1;
+        start = column - 1 + token.len();
+        match token.to_lowercase().as_str() {
+            "bezier" => parsed.kind = Some(CurveKind::Bezier),
+            "bspline" => parsed.kind = Some(CurveKind::BSpline),
+            "nurbs" => parsed.kind = Some(CurveKind::Nurbs),
+            "2d" => parsed.dimension = 2,
+            "3d" => parsed.dimension = 3,
+            _ => {
+                return Err(ParseError::new(
+                    file,
+                    line,
+                    column,
+                    format!("expected a degree or a header, found {token:?}"),
+                ))
+            }
+        }
+    }
+    Ok(Some(()))
+}
+
+// `expected` stands in for the curve type of files without a header.
+fn parse(
+    file: &str,
+    contents: &str,
+    expected: Option<CurveKind>,
+) -> Result<(CurveFile, Positions), ParseError> {
+    let mut parsed = CurveFile {
+        kind: None,
+        dimension: 3,
+        degree: 0,
+        control_points: Vec::new(),
+        weights: None,
+        knots: None,
+    };
+    let unknown = Position { line: 0, column: 0 };
+    let mut positions = Positions {
+        header: unknown,
+        degree: unknown,
+        knots: unknown,
+    };
+    let mut has_header = false;
+    let mut has_degree = false;
+    let mut weights = Vec::new();
+
+    for (index, line) in contents.lines().enumerate() {
+        let line_number = index + 1;
+        let text = line.split('#').next().unwrap();
+        if text.trim().is_empty() {
+            continue;
+        }
+        let position = Position {
+            line: line_number,
+            column: text.len() - text.trim_start().len() + 1,
+        };
+
+        if !has_header && !has_degree {
+            has_header = true;
+            if parse_header(file, line_number, text, &mut parsed)?.is_some() {
+                positions.header = position;
+                continue;
+            }
+        }
+
+        if !has_degree {
+            has_degree = true;
+            positions.degree = position;
+            parsed.degree = text.trim().parse::<usize>().map_err(|_| {
+                ParseError::at(
+                    file,
+                    position,
+                    format!("expected a degree, found {:?}", text.trim()),
+                )
+            })?;
+            continue;
+        }
+
+        if let Some(knots) = text.trim_start().strip_prefix("knots") {
+            if parsed.knots.is_some() || !parsed.control_points.is_empty() {
+                return Err(ParseError::at(
+                    file,
+                    position,
+                    "the knots must come once, right after the degree",
+                ));
+            }
+            positions.knots = position;
+            let offset = text.len() - knots.len();
+            parsed.knots = Some(numbers(file, line_number, &fields(knots, offset))?);
+            continue;
+        }
+
+        let fields = fields(text, 0);
+        let dimension = parsed.dimension;
+        let rational = parsed.kind.or(expected) == Some(CurveKind::Nurbs);
+        let weighted = rational && fields.len() == dimension + 1;
+        if fields.len() != dimension && !weighted {
+            let reason = if rational {
+                format!(
+                    "expected {} coordinates and an optional weight, found {} fields",
+                    dimension,
+                    fields.len()
+                )
+            } else {
+                format!("expected {} coordinates, found {}", dimension, fields.len())
+            };
+            return Err(ParseError::new(file, line_number, 1, reason));
+        }
+
+        let mut point = numbers(file, line_number, &fields)?;
+        let weight = if weighted { point.pop().unwrap() } else { 1.0 };
+        if weight <= 0.0 || !weight.is_finite() {
+            return Err(ParseError::new(
+                file,
+                line_number,
+                fields[dimension].0,
+                format!("weights must be positive, found {weight}"),
+            ));
+        }
+        point.resize(3, 0.0);
+        parsed.control_points.push(point);
+        weights.push(weight);
+    }
+
+    if parsed.control_points.is_empty() {
+        return Err(ParseError::new(file, 0, 0, "no control points"));
+    }
+    if parsed.kind.or(expected) == Some(CurveKind::Nurbs) {
+        parsed.weights = Some(weights);
+    }
+
+    Ok((parsed, positions))
+}
+
+// A Bézier curve of degree n has exactly n + 1 control points. Like the rest of `bspline`, the
+// degree of B-Splines and NURBS is their order, which cannot exceed the number of control points.
+fn validate(
+    file: &str,
+    parsed: &CurveFile,
+    kind: CurveKind,
+    positions: &Positions,
+) -> Result<(), ParseError> {
+    let (degree, count) = (parsed.degree, parsed.control_points.len());
+    if kind == CurveKind::Bezier {
+        if degree + 1 != count {
+            return Err(ParseError::at(
+                file,
+                positions.degree,
+                format!(
+                    "a Bézier curve of degree {} needs {} control points, found {}",
+                    degree,
+                    degree + 1,
+                    count
+                ),
+            ));
+        }
+        if parsed.knots.is_some() {
+            return Err(ParseError::at(
+                file,
+                positions.knots,
+                "Bézier curves do not take knots",
+            ));
+        }
+        return Ok(());
+    }
+
+    if degree == 0 {
+        return Err(ParseError::at(
+            file,
+            positions.degree,
+            format!("the order of a {kind} curve must be at least 1"),
+        ));
+    }
+    if degree > count {
+        return Err(ParseError::at(
+            file,
+            positions.degree,
+            format!(
+                "a {kind} curve of order {degree} needs at least {degree} control points, found {count}"
+            ),
+        ));
+    }
+    if let Some(knots) = &parsed.knots {
+        if knots.len() != count + degree {
+            return Err(ParseError::at(
+                file,
+                positions.knots,
+                format!(
+                    "{} control points of order {} need {} knots, found {}",
+                    count,
+                    degree,
+                    count + degree,
+                    knots.len()
+                ),
+            ));
+        }
+        if knots.windows(2).any(|pair| pair[1] < pair[0]) {
+            return Err(ParseError::at(
+                file,
+                positions.knots,
+                "the knots must be non-decreasing",
+            ));
+        }
+        if knots[degree - 1] == knots[count] {
+            return Err(ParseError::at(
+                file,
+                positions.knots,
+                format!(
+                    "the domain knots[{}]..knots[{}] must not be empty, both are {}",
+                    degree - 1,
+                    count,
+                    knots[count]
+                ),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+fn load(file: &str, expected: Option<CurveKind>) -> Result<(CurveFile, Positions), ParseError> {
+    let contents =
+        fs::read_to_string(file).map_err(|error| ParseError::new(file, 0, 0, error.to_string()))?;
+    parse(file, &contents, expected)
+}
+
+// Files without a header are validated as B-Splines, their original meaning.
+pub fn read_curve(file: &str) -> Result<CurveFile, ParseError> {
+    let (parsed, positions) = load(file, None)?;
+    validate(
+        file,
+        &parsed,
+        parsed.kind.unwrap_or(CurveKind::BSpline),
+        &positions,
+    )?;
+    Ok(parsed)
+}
+
+// Reads a file of the given kind; files without a header are taken to be of that kind.
+fn read_kind(file: &str, kind: CurveKind) -> Result<CurveFile, ParseError> {
+    let (parsed, positions) = load(file, Some(kind))?;
+    if parsed.kind.is_some_and(|found| found != kind) {
+        return Err(ParseError::at(
+            file,
+            positions.header,
+            format!("expected a {} curve, found {}", kind, parsed.kind.unwrap()),
+        ));
+    }
+    validate(file, &parsed, kind, &positions)?;
+    Ok(parsed)
+}
+
+pub fn read_bezier(file: &str) -> Result<BezierCurve, ParseError> {
+    let parsed = read_kind(file, CurveKind::Bezier)?;
+    Ok(BezierCurve::new(parsed.control_points))
+}
+
+pub fn read_bspline(file: &str) -> Result<BSplineCurve, ParseError> {
+    let parsed = read_kind(file, CurveKind::BSpline)?;
+    let knots = parsed.knots.unwrap_or_else(|| {
+        bspline::generate_knot_vector(parsed.control_points.len() - 1, parsed.degree)
+    });
+    Ok(BSplineCurve::with_knots(
+        parsed.control_points,
+        parsed.degree,
+        knots,
+    ))
+}
+
+pub fn read_nurbs(file: &str) -> Result<NurbsCurve, ParseError> {
+    let parsed = read_kind(file, CurveKind::Nurbs)?;
+    let count = parsed.control_points.len();
+    let knots = parsed
+        .knots
+        .unwrap_or_else(|| bspline::generate_knot_vector(count - 1, parsed.degree));
+    let weights = parsed.weights.unwrap_or_else(|| vec![1.0; count]);
+    Ok(NurbsCurve::with_knots(
+        parsed.control_points,
+        weights,
+        parsed.degree,
+        knots,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read(contents: &str, kind: CurveKind) -> Result<CurveFile, ParseError> {
+        let (parsed, positions) = parse("test", contents, Some(kind))?;
+        validate("test", &parsed, kind, &positions)?;
+        Ok(parsed)
+    }
+
+    fn error_at(contents: &str, kind: CurveKind) -> (usize, usize) {
+        let error = read(contents, kind).unwrap_err();
+        (error.line, error.column)
+    }
+
+    #[test]
+    fn reads_a_nurbs_file() {
+        let parsed = read(
+            "nurbs 2d # a quarter circle\n3\nknots 0, 0, 0, 1, 1, 1\n1, 0\n1, 1, 0.5\n0, 1\n",
+            CurveKind::Nurbs,
+        )
+        .unwrap();
+        assert_eq!(parsed.control_points[1], vec![1.0, 1.0, 0.0]);
+        assert_eq!(parsed.weights, Some(vec![1.0, 0.5, 1.0]));
+        assert_eq!(parsed.knots, Some(vec![0.0, 0.0, 0.0, 1.0, 1.0, 1.0]));
+    }
+
+    #[test]
+    fn rejects_numbers_that_are_not_finite() {
+        assert_eq!(
+            error_at("nurbs\n2\n0, 0, 0\n1, 1, 0, nan\n", CurveKind::Nurbs),
+            (4, 10)
+        );
+        assert_eq!(
+            error_at("2\n0, inf, 0\n1, 1, 0\n", CurveKind::BSpline),
+            (2, 4)
+        );
+        assert_eq!(
+            error_at(
+                "2\nknots 0, 0, NaN, 1\n0, 0, 0\n1, 1, 0\n",
+                CurveKind::BSpline
+            ),
+            (2, 13)
+        );
+    }
+
+    #[test]
+    fn rejects_an_empty_domain() {
+        assert_eq!(
+            error_at(
+                "3\nknots 0, 0, 1, 1, 1, 1\n0, 0, 0\n1, 1, 0\n2, 0, 0\n",
+                CurveKind::BSpline
+            ),
+            (2, 1)
+        );
+    }
+
+    #[test]
+    fn validates_files_without_a_header() {
+        let file = std::env::temp_dir().join("curves-continuity-io-test.txt");
+        fs::write(&file, "0\n0, 0, 0\n1, 1, 0\n").unwrap();
+        let error = read_curve(file.to_str().unwrap()).unwrap_err();
+        fs::remove_file(&file).unwrap();
+        assert_eq!((error.line, error.column), (1, 1));
+    }
+}
@@ -1,4 +1,25 @@
-use crate::{bezier, bspline};
+use crate::curve::BSplineCurve;
+use crate::surface::{self, BSplineSurface, BezierSurface, Surface};
+use crate::{bezier, bspline, construction, vector};
+
+// The derivatives of the B-Spline and of the adjusted Bézier curve at the join, in that order.
+pub type JoinDerivatives = (Vec<f64>, Vec<f64>);
+
+// The part of the B-Spline before `u`, split off with clamped knots, so the next curve can be joined
+// there rather than at the end of the whole B-Spline. At or past the end the curve is kept whole.
+pub fn trim(
+    control_points: &[Vec<f64>],
+    degree: usize,
+    knots: &[f64],
+    u: f64,
+) -> bspline::BSplinePiece {
+    let n = control_points.len() - 1;
+    if u < knots[n + 1] {
+        bspline::split(control_points, degree, knots, u).0
+    } else {
+        (control_points.to_vec(), knots.to_vec())
+    }
+}
 
 pub fn c0_continuity(
     first_control_points: &[Vec<f64>],
@@ -7,7 +28,7 @@ pub fn c0_continuity(
     let first_curve_last_point = &first_control_points[first_control_points.len() - 1];
     let second_curve_first_point = &second_control_points[0];
 
-    let delta = vec![
+    let delta = [
         first_curve_last_point[0] - second_curve_first_point[0],
         first_curve_last_point[1] - second_curve_first_point[1],
         first_curve_last_point[2] - second_curve_first_point[2],
@@ -32,7 +53,7 @@ pub fn c1_continuity(
     first_curve_degree: usize,
     first_curve_knots: &[f64],
     h: f64,
-) -> ((Vec<f64>, Vec<f64>), Vec<Vec<f64>>) {
+) -> (JoinDerivatives, Vec<Vec<f64>>) {
     let mut second_control_points = c0_continuity(first_control_points, second_control_points);
 
     let n = first_control_points.len() - 1;
@@ -64,7 +85,7 @@ pub fn c2_continuity(
     first_curve_degree: usize,
     first_curve_knots: &[f64],
     h: f64,
-) -> ((Vec<f64>, Vec<f64>), Vec<Vec<f64>>) {
+) -> (JoinDerivatives, Vec<Vec<f64>>) {
     let mut second_control_points = c1_continuity(
         first_control_points,
         second_control_points,
@@ -96,3 +117,236 @@ pub fn c2_continuity(
     let d_b = bezier::derivative_bezier(&second_control_points, 0.0, 2);
     ((d_s, d_b), (second_control_points))
 }
+
+// The net as one curve in `v` whose control points each hold a whole column of the net, so knot
+// insertion and degree elevation in `v` act on every row at once.
+fn v_curve(control_points: &[Vec<Vec<f64>>], degree: usize, knots: &[f64]) -> BSplineCurve {
+    let columns = surface::transpose(control_points)
+        .iter()
+        .map(|column| column.concat())
+        .collect();
+    BSplineCurve::with_knots(columns, degree, knots.to_vec())
+}
+
+fn v_net(curve: &BSplineCurve, dim: usize) -> Vec<Vec<Vec<f64>>> {
+    let columns: Vec<Vec<Vec<f64>>> = curve
+        .control_points
+        .iter()
+        .map(|column| column.chunks(dim).map(<[f64]>::to_vec).collect())
+        .collect();
+    surface::transpose(&columns)
+}
+
+// The clamped knots of a single Bézier span of the given order over [0, 1].
+fn bezier_knots(degree: usize) -> Vec<f64> {
+    [vec![0.0; degree], vec![1.0; degree]].concat()
+}
+
+// Surfaces are joined across the `u = end` edge of the B-Spline and the `u = 0` edge of the Bézier
+// patch, one pair of columns at a time. That carries over to the whole edge once both share the
+// basis in `v`, so both are first brought to the same order and knots in `v`, over the `v` domain
+// of the B-Spline. The patch is returned as a B-Spline surface, one Bézier span in `u`.
+fn compatible_edges(
+    first: &BSplineSurface,
+    second: &BezierSurface,
+) -> (BSplineSurface, BSplineSurface) {
+    let dim = first.control_points[0][0].len();
+    let (rows, columns) = (second.control_points.len(), second.control_points[0].len());
+    let curves = construction::compatible(&[
+        v_curve(&first.control_points, first.v_degree, &first.v_knots),
+        v_curve(&second.control_points, columns, &bezier_knots(columns)),
+    ]);
+    let (_, (v_start, v_end)) = first.domain();
+    let curves: Vec<BSplineCurve> = curves
+        .iter()
+        .map(|curve| curve.with_domain(v_start, v_end))
+        .collect();
+    let (v_degree, v_knots) = (curves[0].degree, curves[0].knots.clone());
+
+    (
+        BSplineSurface::with_knots(
+            v_net(&curves[0], dim),
+            first.u_degree,
+            v_degree,
+            first.u_knots.clone(),
+            v_knots.clone(),
+        ),
+        BSplineSurface::with_knots(
+            v_net(&curves[1], dim),
+            rows,
+            v_degree,
+            bezier_knots(rows),
+            v_knots,
+        ),
+    )
+}
+
+fn join_columns(
+    first: &BSplineSurface,
+    second: &BezierSurface,
+    join: impl Fn(&[Vec<f64>], &[Vec<f64>]) -> Vec<Vec<f64>>,
+) -> BSplineSurface {
+    let (first, second) = compatible_edges(first, second);
+    let columns: Vec<Vec<Vec<f64>>> = surface::transpose(&first.control_points)
+        .iter()
+        .zip(&surface::transpose(&second.control_points))
+        .map(|(first_column, second_column)| join(first_column, second_column))
+        .collect();
+
+    BSplineSurface::with_knots(
+        surface::transpose(&columns),
+        second.u_degree,
+        second.v_degree,
+        second.u_knots,
+        second.v_knots,
+    )
+}
+
+pub fn surface_c0_continuity(first: &BSplineSurface, second: &BezierSurface) -> BSplineSurface {
+    join_columns(first, second, c0_continuity)
+}
+
+pub fn surface_c1_continuity(
+    first: &BSplineSurface,
+    second: &BezierSurface,
+    h: f64,
+) -> BSplineSurface {
+    join_columns(first, second, |first_column, second_column| {
+        c1_continuity(
+            first_column,
+            second_column,
+            first.u_degree,
+            &first.u_knots,
+            h,
+        )
+        .1
+    })
+}
+
+pub fn surface_c2_continuity(
+    first: &BSplineSurface,
+    second: &BezierSurface,
+    h: f64,
+) -> BSplineSurface {
+    join_columns(first, second, |first_column, second_column| {
+        c2_continuity(
+            first_column,
+            second_column,
+            first.u_degree,
+            &first.u_knots,
+            h,
+        )
+        .1
+    })
+}
+
+// G1 only asks for the cross-boundary derivatives to be parallel, so the C1 ones are scaled by
+// `ratio`. It has to be the same along the whole edge, or the normals would not line up between
+// the control points. There is no G1 join for a ratio that is not positive, which would flip or
+// collapse the cross-boundary derivative.
+pub fn surface_g1_continuity(
+    first: &BSplineSurface,
+    second: &BezierSurface,
+    ratio: f64,
+    h: f64,
+) -> Option<BSplineSurface> {
+    if !(ratio > 0.0 && ratio.is_finite()) {
+        return None;
+    }
+    Some(join_columns(
+        first,
+        second,
+        |first_column, second_column| {
+            let mut column = c1_continuity(
+                first_column,
+                second_column,
+                first.u_degree,
+                &first.u_knots,
+                h,
+            )
+            .1;
+            let cross_derivative = vector::sub(&column[1], &column[0]);
+            column[1] = vector::add(&column[0], &vector::scale(&cross_derivative, ratio));
+            column
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A cubic B-Spline surface with interior knots in `v`, whose v domain is [0, 3].
+    fn bspline_surface() -> BSplineSurface {
+        let control_points = (0..4)
+            .map(|i| {
+                (0..6)
+                    .map(|j| {
+                        let (x, y) = (i as f64, j as f64);
+                        vec![x, y, (x * 0.7).sin() + (y * 1.3).cos() + 0.1 * x * y]
+                    })
+                    .collect()
+            })
+            .collect();
+        BSplineSurface::new(control_points, 4, 4)
+    }
+
+    // A quadratic-by-linear patch, unrelated to the B-Spline until it is joined.
+    fn bezier_patch() -> BezierSurface {
+        BezierSurface::new(vec![
+            vec![vec![0.0, 0.0, 1.0], vec![0.0, 2.0, 0.0]],
+            vec![vec![1.0, 0.0, 2.0], vec![1.0, 2.0, 1.0]],
+            vec![vec![2.0, 0.0, 0.0], vec![2.0, 2.0, 3.0]],
+        ])
+    }
+
+    // Compares the derivative of order `k` across the edge, scaled by `ratio^k` on the B-Spline side.
+    fn assert_edge_matches(joined: &BSplineSurface, k: usize, ratio: f64) {
+        let first = bspline_surface();
+        let ((_, u_end), (v_start, v_end)) = first.domain();
+        assert_eq!(joined.domain().1, (v_start, v_end));
+        for step in 0..=30 {
+            let v = v_start + (v_end - v_start) * step as f64 / 30.0;
+            let expected = vector::scale(&first.derivative(u_end, v, k, 0), ratio.powi(k as i32));
+            let actual = joined.derivative(0.0, v, k, 0);
+            assert!(
+                vector::distance(&expected, &actual) < 1e-6 * (1.0 + vector::norm(&expected)),
+                "v = {}: {:?} != {:?}",
+                v,
+                expected,
+                actual
+            );
+        }
+    }
+
+    #[test]
+    fn joins_across_interior_knots_in_v() {
+        let (first, second) = (bspline_surface(), bezier_patch());
+        assert_edge_matches(&surface_c0_continuity(&first, &second), 0, 1.0);
+
+        let joined = surface_c1_continuity(&first, &second, 1e-9);
+        assert_edge_matches(&joined, 0, 1.0);
+        assert_edge_matches(&joined, 1, 1.0);
+
+        let joined = surface_c2_continuity(&first, &second, 1e-9);
+        for k in 0..=2 {
+            assert_edge_matches(&joined, k, 1.0);
+        }
+    }
+
+    #[test]
+    fn g1_scales_the_cross_boundary_derivative() {
+        let (first, second) = (bspline_surface(), bezier_patch());
+        let joined = surface_g1_continuity(&first, &second, 0.5, 1e-9).unwrap();
+        assert_edge_matches(&joined, 0, 1.0);
+        // The derivative across the edge only has to keep its direction.
+        let ((_, u_end), _) = first.domain();
+        for v in [0.0, 0.7, 1.5, 2.9] {
+            let expected = vector::scale(&first.derivative(u_end, v, 1, 0), 0.5);
+            assert!(vector::distance(&expected, &joined.derivative(0.0, v, 1, 0)) < 1e-6);
+        }
+
+        assert!(surface_g1_continuity(&first, &second, 0.0, 1e-9).is_none());
+        assert!(surface_g1_continuity(&first, &second, f64::NAN, 1e-9).is_none());
+    }
+}
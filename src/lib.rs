@@ -0,0 +1,29 @@
+pub mod approximation;
+pub mod arc_length;
+pub mod bezier;
+pub mod bounds;
+pub mod bspline;
+pub mod construction;
+pub mod curve;
+pub mod differential;
+pub mod fairing;
+pub mod inflection;
+pub mod interpolation;
+pub mod intersection;
+pub mod io;
+pub mod join;
+pub mod linalg;
+pub mod nurbs;
+pub mod offset;
+#[cfg(feature = "plotly")]
+pub mod plotly_renderer;
+pub mod plotter;
+pub mod projection;
+pub mod render;
+#[cfg(feature = "serde")]
+pub mod scene;
+#[cfg(feature = "serde")]
+pub mod serialization;
+pub mod surface;
+pub mod svg;
+pub mod vector;
@@ -0,0 +1,41 @@
+// Gaussian elimination with partial pivoting. Each column of `rhs` is solved for at once, which
+// is how the coordinates of control points are found together. Returns `None` for singular systems.
+pub fn solve(mut matrix: Vec<Vec<f64>>, mut rhs: Vec<Vec<f64>>) -> Option<Vec<Vec<f64>>> {
+    let size = matrix.len();
+
+    for column in 0..size {
+        let pivot = (column..size)
+            .max_by(|&a, &b| matrix[a][column].abs().total_cmp(&matrix[b][column].abs()))?;
+        if matrix[pivot][column].abs() <= f64::EPSILON {
+            return None;
+        }
+        matrix.swap(column, pivot);
+        rhs.swap(column, pivot);
+
+        let (pivot_row, pivot_rhs) = (matrix[column].clone(), rhs[column].clone());
+        for row in column + 1..size {
+            let factor = matrix[row][column] / pivot_row[column];
+            if factor == 0.0 {
+                continue;
+            }
+            for (value, pivot_value) in matrix[row][column..].iter_mut().zip(&pivot_row[column..]) {
+                *value -= factor * pivot_value;
+            }
+            for (value, pivot_value) in rhs[row].iter_mut().zip(&pivot_rhs) {
+                *value -= factor * pivot_value;
+            }
+        }
+    }
+
+    let mut solution = rhs.clone();
+    for row in (0..size).rev() {
+        for k in 0..solution[row].len() {
+            let known: f64 = (row + 1..size)
+                .map(|j| matrix[row][j] * solution[j][k])
+                .sum();
+            solution[row][k] = (rhs[row][k] - known) / matrix[row][row];
+        }
+    }
+
+    Some(solution)
+}
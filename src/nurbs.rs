@@ -0,0 +1,65 @@
+use crate::bspline;
+
+pub(crate) fn binomial(n: usize, k: usize) -> f64 {
+    (0..k).fold(1.0, |product, i| product * (n - i) as f64 / (i + 1) as f64)
+}
+
+// Control points lifted to 4D as (w x, w y, w z, w), where the curve is an ordinary B-Spline.
+pub(crate) fn homogeneous(control_points: &[Vec<f64>], weights: &[f64]) -> Vec<Vec<f64>> {
+    assert_eq!(control_points.len(), weights.len());
+    control_points
+        .iter()
+        .zip(weights)
+        .map(|(point, &weight)| {
+            let mut lifted: Vec<f64> = point.iter().map(|coord| coord * weight).collect();
+            lifted.push(weight);
+            lifted
+        })
+        .collect()
+}
+
+pub fn nurbs(
+    control_points: &[Vec<f64>],
+    weights: &[f64],
+    u: f64,
+    degree: usize,
+    knots: &[f64],
+) -> Vec<f64> {
+    derivative_nurbs(control_points, weights, u, 0, degree, knots)
+}
+
+// With A and w the numerator and the weight of the homogeneous curve, C = A / w and
+// C^(k) = (A^(k) - sum_{i=1}^{k} binom(k, i) w^(i) C^(k-i)) / w (Piegl & Tiller eq. 4.8).
+pub fn derivative_nurbs(
+    control_points: &[Vec<f64>],
+    weights: &[f64],
+    u: f64,
+    k: usize,
+    degree: usize,
+    knots: &[f64],
+) -> Vec<f64> {
+    let lifted = homogeneous(control_points, weights);
+    let dim = control_points[0].len();
+    let derivatives: Vec<Vec<f64>> = (0..=k)
+        .map(|order| bspline::derivative_bspline(&lifted, u, order, degree, knots))
+        .collect();
+
+    let mut curve_derivatives: Vec<Vec<f64>> = Vec::with_capacity(k + 1);
+    for order in 0..=k {
+        let mut numerator = derivatives[order][..dim].to_vec();
+        for i in 1..=order {
+            let factor = binomial(order, i) * derivatives[i][dim];
+            for (coord, lower) in numerator.iter_mut().zip(&curve_derivatives[order - i]) {
+                *coord -= factor * lower;
+            }
+        }
+        curve_derivatives.push(
+            numerator
+                .iter()
+                .map(|coord| coord / derivatives[0][dim])
+                .collect(),
+        );
+    }
+
+    curve_derivatives.pop().unwrap()
+}
@@ -0,0 +1,282 @@
+use crate::curve::{BSplineCurve, BezierCurve, Curve};
+use crate::{bezier, bspline, vector};
+
+const MAX_DEPTH: usize = 16;
+const ERROR_SAMPLES: usize = 8;
+
+pub struct Offset {
+    pub curve: BSplineCurve,
+    // Parameters of the original curve where the offset distance equals the radius of curvature.
+    pub cusps: Vec<f64>,
+    // Ranges of the original curve where the offset runs backwards, forming a loop.
+    pub reversed_ranges: Vec<(f64, f64)>,
+}
+
+// Signed curvature of the projection of the curve onto the xy plane.
+fn signed_curvature(curve: &impl Curve, u: f64) -> f64 {
+    let d1 = curve.derivative(u, 1);
+    let d2 = curve.derivative(u, 2);
+    (d1[0] * d2[1] - d1[1] * d2[0]) / (d1[0] * d1[0] + d1[1] * d1[1]).powf(1.5)
+}
+
+// Unit normal to the left of the direction of travel, in the xy plane.
+fn left_normal(curve: &impl Curve, u: f64) -> Vec<f64> {
+    let d1 = curve.derivative(u, 1);
+    let speed = (d1[0] * d1[0] + d1[1] * d1[1]).sqrt();
+    let mut normal = vec![0.0; d1.len()];
+    normal[0] = -d1[1] / speed;
+    normal[1] = d1[0] / speed;
+    normal
+}
+
+fn offset_point(curve: &impl Curve, u: f64, distance: f64) -> Vec<f64> {
+    vector::add(
+        &curve.point(u),
+        &vector::scale(&left_normal(curve, u), distance),
+    )
+}
+
+// The normal turns with the tangent, so O' = C' (1 - d κ).
+fn offset_derivative(curve: &impl Curve, u: f64, distance: f64) -> Vec<f64> {
+    vector::scale(
+        &curve.derivative(u, 1),
+        1.0 - distance * signed_curvature(curve, u),
+    )
+}
+
+// Cubic Hermite interpolation of the offset over [start, end], as a Bézier segment.
+fn hermite_segment(curve: &impl Curve, start: f64, end: f64, distance: f64) -> Vec<Vec<f64>> {
+    let third = (end - start) / 3.0;
+    let first = offset_point(curve, start, distance);
+    let last = offset_point(curve, end, distance);
+    let first_tangent = vector::scale(&offset_derivative(curve, start, distance), third);
+    let last_tangent = vector::scale(&offset_derivative(curve, end, distance), third);
+
+    vec![
+        first.clone(),
+        vector::add(&first, &first_tangent),
+        vector::sub(&last, &last_tangent),
+        last,
+    ]
+}
+
+fn approximate(
+    curve: &impl Curve,
+    (start, end): (f64, f64),
+    distance: f64,
+    tolerance: f64,
+    depth: usize,
+    segments: &mut Vec<(Vec<Vec<f64>>, f64)>,
+) {
+    let segment = hermite_segment(curve, start, end, distance);
+    let error = (1..ERROR_SAMPLES)
+        .map(|j| {
+            let t = j as f64 / ERROR_SAMPLES as f64;
+            vector::distance(
+                &bezier::bezier(&segment, t),
+                &offset_point(curve, start + t * (end - start), distance),
+            )
+        })
+        .fold(0.0, f64::max);
+
+    if error <= tolerance || depth == MAX_DEPTH {
+        segments.push((segment, end));
+        return;
+    }
+
+    let middle = 0.5 * (start + end);
+    approximate(
+        curve,
+        (start, middle),
+        distance,
+        tolerance,
+        depth + 1,
+        segments,
+    );
+    approximate(
+        curve,
+        (middle, end),
+        distance,
+        tolerance,
+        depth + 1,
+        segments,
+    );
+}
+
+fn bisect(f: impl Fn(f64) -> f64, mut start: f64, mut end: f64) -> f64 {
+    let start_sign = f(start) > 0.0;
+    for _ in 0..60 {
+        let middle = 0.5 * (start + end);
+        if (f(middle) > 0.0) == start_sign {
+            start = middle;
+        } else {
+            end = middle;
+        }
+    }
+    0.5 * (start + end)
+}
+
+// Offsets a curve lying in the xy plane by `distance` to the left of its direction of travel (use
+// a negative distance for the right side). `breakpoints` are the parameters where the curve may lose
+// smoothness, such as its knots.
+pub fn offset(curve: &impl Curve, breakpoints: &[f64], distance: f64, tolerance: f64) -> Offset {
+    // Half of the tolerance goes to the Hermite approximation and half to the knot removal.
+    let mut segments = Vec::new();
+    for span in breakpoints.windows(2) {
+        approximate(
+            curve,
+            (span[0], span[1]),
+            distance,
+            0.5 * tolerance,
+            0,
+            &mut segments,
+        );
+    }
+
+    let mut control_points = vec![segments[0].0[0].clone()];
+    let mut knots = vec![breakpoints[0]; 4];
+    for (segment, end) in &segments {
+        control_points.extend_from_slice(&segment[1..]);
+        knots.extend([*end; 3]);
+    }
+    knots.push(breakpoints[breakpoints.len() - 1]);
+
+    let removal = bspline::remove_knots(&control_points, 4, &knots, 0.5 * tolerance);
+    let curve_approximation = BSplineCurve::with_knots(removal.control_points, 4, removal.knots);
+
+    // The offset reverses wherever d κ > 1, with cusps at both ends of those ranges.
+    let factor = |u: f64| 1.0 - distance * signed_curvature(curve, u);
+    let (start, end) = curve.domain();
+    let samples = 1000;
+    let mut cusps = Vec::new();
+    let mut previous = (start, factor(start));
+    for j in 1..=samples {
+        let u = start + (end - start) * j as f64 / samples as f64;
+        let value = factor(u);
+        if (value > 0.0) != (previous.1 > 0.0) {
+            cusps.push(bisect(factor, previous.0, u));
+        }
+        previous = (u, value);
+    }
+
+    let mut boundaries = cusps.clone();
+    if factor(start) < 0.0 {
+        boundaries.insert(0, start);
+    }
+    if factor(end) < 0.0 {
+        boundaries.push(end);
+    }
+    let reversed_ranges = boundaries
+        .chunks_exact(2)
+        .map(|pair| (pair[0], pair[1]))
+        .collect();
+
+    Offset {
+        curve: curve_approximation,
+        cusps,
+        reversed_ranges,
+    }
+}
+
+pub fn offset_bezier(control_points: &[Vec<f64>], distance: f64, tolerance: f64) -> Offset {
+    offset(
+        &BezierCurve::new(control_points.to_vec()),
+        &[0.0, 1.0],
+        distance,
+        tolerance,
+    )
+}
+
+pub fn offset_bspline(
+    control_points: &[Vec<f64>],
+    degree: usize,
+    knots: &[f64],
+    distance: f64,
+    tolerance: f64,
+) -> Offset {
+    let mut breakpoints = knots[degree - 1..=control_points.len()].to_vec();
+    breakpoints.dedup();
+
+    offset(
+        &BSplineCurve::with_knots(control_points.to_vec(), degree, knots.to_vec()),
+        &breakpoints,
+        distance,
+        tolerance,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curve::NurbsCurve;
+
+    // The quarter of the unit circle from (1, 0) to (0, 1), travelled counterclockwise.
+    fn quarter_circle() -> NurbsCurve {
+        NurbsCurve::with_knots(
+            vec![
+                vec![1.0, 0.0, 0.0],
+                vec![1.0, 1.0, 0.0],
+                vec![0.0, 1.0, 0.0],
+            ],
+            vec![1.0, std::f64::consts::FRAC_1_SQRT_2, 1.0],
+            3,
+            vec![0.0, 0.0, 0.0, 1.0, 1.0, 1.0],
+        )
+    }
+
+    fn parameters() -> impl Iterator<Item = f64> {
+        (0..=100).map(|j| j as f64 / 100.0)
+    }
+
+    #[test]
+    fn offset_of_a_segment_is_a_parallel_segment() {
+        let offset = offset_bezier(&[vec![0.0, 0.0, 0.0], vec![4.0, 3.0, 0.0]], 1.0, 1e-6);
+        for u in parameters() {
+            let expected = [4.0 * u - 0.6, 3.0 * u + 0.8, 0.0];
+            assert!(vector::distance(&offset.curve.point(u), &expected) <= 1e-6);
+        }
+        assert!(offset.cusps.is_empty() && offset.reversed_ranges.is_empty());
+    }
+
+    #[test]
+    fn offset_of_an_arc_stays_within_the_tolerance() {
+        let tolerance = 1e-4;
+        let circle = quarter_circle();
+        // The left normal of a counterclockwise circle points to its centre.
+        for (distance, radius) in [(0.25, 0.75), (-0.5, 1.5)] {
+            let offset = offset(&circle, &[0.0, 1.0], distance, tolerance);
+            for u in parameters() {
+                let expected = vector::scale(&circle.point(u), radius);
+                assert!(vector::distance(&offset.curve.point(u), &expected) <= tolerance);
+            }
+            assert!(offset.cusps.is_empty() && offset.reversed_ranges.is_empty());
+        }
+    }
+
+    #[test]
+    fn tight_turns_reverse_the_offset() {
+        // y = x² for x in [-1, 1], with x = 2t - 1. Its curvature 2 / (1 + 4x²)^1.5 exceeds 1 for
+        // 1 + 4x² < 2^(2/3), so an offset of 1 towards the inside loops back around the vertex.
+        let parabola = [
+            vec![-1.0, 1.0, 0.0],
+            vec![0.0, -1.0, 0.0],
+            vec![1.0, 1.0, 0.0],
+        ];
+        let x = ((2.0_f64.powf(2.0 / 3.0) - 1.0) / 4.0).sqrt();
+        let (first, second) = ((1.0 - x) / 2.0, (1.0 + x) / 2.0);
+
+        let offset = offset_bezier(&parabola, 1.0, 1e-4);
+        assert_eq!(offset.cusps.len(), 2);
+        assert!((offset.cusps[0] - first).abs() < 1e-9);
+        assert!((offset.cusps[1] - second).abs() < 1e-9);
+        assert_eq!(
+            offset.reversed_ranges,
+            vec![(offset.cusps[0], offset.cusps[1])]
+        );
+
+        // Offsetting further than the flattest radius reverses all of it.
+        let offset = offset_bezier(&parabola, 100.0, 1e-2);
+        assert!(offset.cusps.is_empty());
+        assert_eq!(offset.reversed_ranges, vec![(0.0, 1.0)]);
+    }
+}
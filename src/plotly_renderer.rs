@@ -0,0 +1,70 @@
+use crate::plotter::Format;
+use crate::render::{Figure, RenderError, Renderer, Style};
+use plotly::common::{DashType, Line, Marker, Mode, Title};
+use plotly::layout::Axis;
+use plotly::{ImageFormat, Plot, Scatter};
+
+// Interactive HTML through plotly, and the static formats through kaleido, which downloads and runs
+// a Chromium-based binary.
+pub struct PlotlyRenderer;
+
+fn plot(figure: &Figure) -> Plot {
+    let mut plot = Plot::new();
+    for trace in &figure.traces {
+        let scatter =
+            Scatter::new(trace.x_values.clone(), trace.y_values.clone()).name(&trace.name);
+        let scatter = match trace.style {
+            Style::Line { dashed, width } => {
+                let dash_type = if dashed {
+                    DashType::Dash
+                } else {
+                    DashType::Solid
+                };
+                scatter
+                    .mode(Mode::Lines)
+                    .line(Line::new().dash(dash_type).width(width))
+            }
+            Style::Markers { size } => scatter
+                .mode(Mode::Markers)
+                .marker(Marker::new().size(size as usize)),
+        };
+        plot.add_trace(scatter);
+    }
+
+    let mut layout = plotly::Layout::new()
+        .width(figure.width)
+        .height(figure.height)
+        .title(Title::from(figure.title.as_str()));
+    if let Some((start, end)) = figure.x_range {
+        layout = layout.x_axis(Axis::new().range(vec![start, end]));
+    }
+    if let Some((start, end)) = figure.y_range {
+        layout = layout.y_axis(Axis::new().range(vec![start, end]));
+    }
+    plot.set_layout(layout);
+
+    plot
+}
+
+impl Renderer for PlotlyRenderer {
+    fn render(&self, figure: &Figure, filename: &str, format: Format) -> Result<(), RenderError> {
+        let file = format!("{}.{}", filename, format.extension());
+        let plot = plot(figure);
+        let image_format = match format {
+            Format::Html => {
+                plot.write_html(file);
+                return Ok(());
+            }
+            Format::Svg => ImageFormat::SVG,
+            Format::Png => ImageFormat::PNG,
+            Format::Pdf => ImageFormat::PDF,
+        };
+        plot.write_image(file, image_format, figure.width, figure.height, 1.0);
+        Ok(())
+    }
+
+    fn show(&self, figure: &Figure) -> Result<(), RenderError> {
+        plot(figure).show();
+        Ok(())
+    }
+}
@@ -1,60 +1,239 @@
-use plotly::common::{DashType, Marker, Mode, Title};
-use plotly::{Plot, Scatter};
+use crate::bounds::{self, BoundingBox};
+use crate::curve::Curve;
+use crate::differential;
+use crate::render::{Figure, NativeRenderer, RenderError, Renderer, Style, Trace};
+#[cfg(feature = "serde")]
+use serde::Deserialize;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum Format {
+    Html,
+    Svg,
+    Png,
+    Pdf,
+}
+
+impl Format {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Format::Html => "html",
+            Format::Svg => "svg",
+            Format::Png => "png",
+            Format::Pdf => "pdf",
+        }
+    }
+}
 
 pub struct Plotter {
-    plot: Plot,
+    traces: Vec<Trace>,
+    bounds: Option<BoundingBox>,
+    renderer: Box<dyn Renderer>,
 }
 
 impl Plotter {
+    // Which renderer a plot goes to is a choice made by the caller, so there is no `Default`.
+    #[allow(clippy::new_without_default)]
     pub fn new() -> Self {
-        Self { plot: Plot::new() }
+        Self::with_renderer(Box::new(NativeRenderer::default()))
     }
 
-    pub fn line(&mut self, x_values: Vec<f64>, y_values: Vec<f64>, name: &str, is_dashed: bool) {
-        let dash_type: DashType = if is_dashed {
-            plotly::common::DashType::Dash
-        } else {
-            plotly::common::DashType::Solid
-        };
+    pub fn with_renderer(renderer: Box<dyn Renderer>) -> Self {
+        Self {
+            traces: Vec::new(),
+            bounds: None,
+            renderer,
+        }
+    }
 
-        let trace = Scatter::new(x_values, y_values)
-            .mode(Mode::Lines)
-            .name(name)
-            .line(plotly::common::Line::new().dash(dash_type));
+    fn add_trace(
+        &mut self,
+        x_values: Vec<Option<f64>>,
+        y_values: Vec<Option<f64>>,
+        name: &str,
+        style: Style,
+    ) {
+        self.traces.push(Trace {
+            name: name.to_string(),
+            x_values,
+            y_values,
+            style,
+        });
+    }
 
-        self.plot.add_trace(trace);
+    pub fn line(&mut self, x_values: Vec<f64>, y_values: Vec<f64>, name: &str, is_dashed: bool) {
+        self.add_trace(
+            x_values.into_iter().map(Some).collect(),
+            y_values.into_iter().map(Some).collect(),
+            name,
+            Style::Line {
+                dashed: is_dashed,
+                width: 2.0,
+            },
+        );
     }
 
     pub fn markers(&mut self, x_values: Vec<f64>, y_values: Vec<f64>, name: &str) {
-        let markers = Scatter::new(x_values, y_values)
-            .mode(Mode::Markers)
-            .name(name)
-            .marker(Marker::new().size(10));
-
-        self.plot.add_trace(markers);
+        self.add_trace(
+            x_values.into_iter().map(Some).collect(),
+            y_values.into_iter().map(Some).collect(),
+            name,
+            Style::Markers { size: 10.0 },
+        );
     }
 
-    pub fn plot(&mut self, title: &str, filename: &str, show: bool, save: bool) {
-        self.plot.set_layout(
-            plotly::Layout::new()
-                .width(1600)
-                .height(900)
-                .title(Title::from(title)),
+    pub fn comb(&mut self, spikes: &[(Vec<f64>, Vec<f64>)], name: &str) {
+        // Each spike is its own segment, split from the next one by a gap.
+        let mut x_values = Vec::with_capacity(3 * spikes.len());
+        let mut y_values = Vec::with_capacity(3 * spikes.len());
+        for (point, tip) in spikes {
+            x_values.extend([Some(point[0]), Some(tip[0]), None]);
+            y_values.extend([Some(point[1]), Some(tip[1]), None]);
+        }
+
+        self.add_trace(
+            x_values,
+            y_values,
+            name,
+            Style::Line {
+                dashed: false,
+                width: 0.5,
+            },
         );
 
+        let ends: Vec<Vec<f64>> = spikes
+            .iter()
+            .flat_map(|(point, tip)| [point.clone(), tip.clone()])
+            .collect();
+        self.fit(&bounds::control_box(&ends));
+
+        let (x_tips, y_tips): (Vec<f64>, Vec<f64>) =
+            spikes.iter().map(|(_, tip)| (tip[0], tip[1])).unzip();
+        self.line(x_tips, y_tips, &format!("{} Envelope", name), false);
+    }
+
+    pub fn curvature_comb(&mut self, curve: &impl Curve, samples: usize, scale: f64, name: &str) {
+        let spikes = differential::curvature_comb(curve, samples, scale);
+        self.comb(&spikes, name);
+    }
+
+    // Plots curvature against the parameter, shifted by `offset` so consecutive curves of a join can
+    // share the same axis.
+    pub fn curvature(&mut self, curve: &impl Curve, samples: usize, offset: f64, name: &str) {
+        let (start, _) = curve.domain();
+        let (parameters, curvatures): (Vec<f64>, Vec<f64>) =
+            differential::sample_parameters(curve, samples)
+                .into_iter()
+                .map(|u| (offset + u - start, differential::curvature(curve, u)))
+                .unzip();
+        let points: Vec<Vec<f64>> = parameters
+            .iter()
+            .zip(&curvatures)
+            .map(|(&u, &curvature)| vec![u, curvature])
+            .collect();
+        self.fit(&bounds::control_box(&points));
+
+        self.line(parameters, curvatures, name, false);
+    }
+
+    // Makes sure the given box is shown, the axes cover every box passed so far.
+    pub fn fit(&mut self, bounding_box: &BoundingBox) {
+        self.bounds = Some(match &self.bounds {
+            Some(bounds) => bounds.union(bounding_box),
+            None => bounding_box.clone(),
+        });
+    }
+
+    // Saves in the same formats as a scene by default. PDF needs the plotly renderer.
+    pub fn plot(
+        &mut self,
+        title: &str,
+        filename: &str,
+        show: bool,
+        save: bool,
+    ) -> Result<(), RenderError> {
+        let formats: &[Format] = if save {
+            &[Format::Html, Format::Svg, Format::Png]
+        } else {
+            &[]
+        };
+        self.plot_formats(title, filename, show, formats)
+    }
+
+    pub fn plot_formats(
+        &mut self,
+        title: &str,
+        filename: &str,
+        show: bool,
+        formats: &[Format],
+    ) -> Result<(), RenderError> {
+        let mut figure = Figure {
+            title: title.to_string(),
+            width: 1600,
+            height: 900,
+            x_range: None,
+            y_range: None,
+            traces: self.traces.clone(),
+        };
+        if let Some(bounds) = &self.bounds {
+            let margin = 0.05 * bounds.diagonal();
+            figure.x_range = Some((bounds.min[0] - margin, bounds.max[0] + margin));
+            figure.y_range = Some((bounds.min[1] - margin, bounds.max[1] + margin));
+        }
+
         if show {
-            self.plot.show();
-        }
-        if save {
-            self.plot.write_html(format!("{}.html", filename));
-            self.plot.write_image(
-                format!("{}.svg", filename),
-                plotly::ImageFormat::SVG,
-                1600,
-                900,
-                1.0,
-            );
-            self.plot.write_image(format!("{}.pdf", filename), plotly::ImageFormat::PDF, 1600, 900, 1.0);
+            self.renderer.show(&figure)?;
+        }
+        for &format in formats {
+            self.renderer.render(&figure, filename, format)?;
         }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curve::{BezierCurve, Shape};
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    // Keeps the figures it is shown.
+    struct Shown(Rc<RefCell<Vec<Figure>>>);
+
+    impl Renderer for Shown {
+        fn render(&self, _: &Figure, _: &str, _: Format) -> Result<(), RenderError> {
+            Ok(())
+        }
+
+        fn show(&self, figure: &Figure) -> Result<(), RenderError> {
+            self.0.borrow_mut().push(figure.clone());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn axes_cover_the_fitted_curves() {
+        let shown = Rc::new(RefCell::new(Vec::new()));
+        let mut plotter = Plotter::with_renderer(Box::new(Shown(shown.clone())));
+        // y = 3t(1 - t) peaks at 3/4, well below the control point at 1.5.
+        let curve = Shape::Bezier(BezierCurve::new(vec![
+            vec![0.0, 0.0, 0.0],
+            vec![1.0, 1.5, 0.0],
+            vec![2.0, 0.0, 0.0],
+        ]));
+        plotter.fit(&bounds::shape_box(&curve));
+        plotter.plot_formats("", "", true, &[]).unwrap();
+
+        // A margin of 5% of the diagonal of the box, sqrt(2² + 0.75²), on every side.
+        let margin = 0.05 * 2.0_f64.hypot(0.75);
+        let figure = &shown.borrow()[0];
+        let (x_range, y_range) = (figure.x_range.unwrap(), figure.y_range.unwrap());
+        assert!((x_range.0 + margin).abs() < 1e-12 && (x_range.1 - 2.0 - margin).abs() < 1e-12);
+        assert!((y_range.0 + margin).abs() < 1e-12 && (y_range.1 - 0.75 - margin).abs() < 1e-12);
     }
 }
@@ -0,0 +1,210 @@
+use crate::curve::{BSplineCurve, BezierCurve, Curve};
+use crate::{bezier, bspline, vector};
+
+const MAX_ITERATIONS: usize = 20;
+const MAX_DEPTH: usize = 6;
+const TOLERANCE: f64 = 1e-12;
+
+#[derive(Clone, Debug)]
+pub struct Projection {
+    pub parameter: f64,
+    pub point: Vec<f64>,
+    pub distance: f64,
+}
+
+fn bounding_box_distance(control_points: &[Vec<f64>], point: &[f64]) -> f64 {
+    let mut squared_distance = 0.0;
+    for (j, coord) in point.iter().enumerate() {
+        let min = control_points.iter().map(|p| p[j]).fold(f64::MAX, f64::min);
+        let max = control_points.iter().map(|p| p[j]).fold(f64::MIN, f64::max);
+        let gap = (min - coord).max(coord - max).max(0.0);
+        squared_distance += gap * gap;
+    }
+    squared_distance.sqrt()
+}
+
+// Recursively halves the Bézier, discarding pieces whose control points are all farther than the
+// closest curve point seen so far (the curve lies in their convex hull).
+fn subdivide(
+    control_points: &[Vec<f64>],
+    point: &[f64],
+    (start, end): (f64, f64),
+    depth: usize,
+    best: &mut f64,
+    guesses: &mut Vec<f64>,
+) {
+    let last = control_points.len() - 1;
+    *best = best
+        .min(vector::distance(&control_points[0], point))
+        .min(vector::distance(&control_points[last], point));
+    if bounding_box_distance(control_points, point) > *best {
+        return;
+    }
+
+    let middle = 0.5 * (start + end);
+    if depth == MAX_DEPTH {
+        guesses.push(middle);
+        return;
+    }
+
+    let (left, right) = bezier::split(control_points, 0.5);
+    subdivide(&left, point, (start, middle), depth + 1, best, guesses);
+    subdivide(&right, point, (middle, end), depth + 1, best, guesses);
+}
+
+fn bezier_guesses(control_points: &[Vec<f64>], point: &[f64], domain: (f64, f64)) -> Vec<f64> {
+    let mut best = f64::MAX;
+    let mut guesses = vec![domain.0, domain.1];
+    subdivide(control_points, point, domain, 0, &mut best, &mut guesses);
+    guesses
+}
+
+// Newton iterations on f(u) = C'(u) · (C(u) - P), which vanishes at the closest point.
+pub fn newton(curve: &impl Curve, point: &[f64], mut u: f64) -> f64 {
+    let (start, end) = curve.domain();
+
+    for _ in 0..MAX_ITERATIONS {
+        let difference = vector::sub(&curve.point(u), point);
+        if vector::norm(&difference) <= TOLERANCE {
+            break;
+        }
+
+        let d1 = curve.derivative(u, 1);
+        let d2 = curve.derivative(u, 2);
+        let f = vector::dot(&d1, &difference);
+        let f_prime = vector::dot(&d2, &difference) + vector::dot(&d1, &d1);
+        if f_prime == 0.0 {
+            break;
+        }
+
+        let next = (u - f / f_prime).clamp(start, end);
+        if ((next - u) * vector::norm(&d1)).abs() <= TOLERANCE {
+            u = next;
+            break;
+        }
+        u = next;
+    }
+
+    u
+}
+
+fn projection_at(curve: &impl Curve, point: &[f64], parameter: f64) -> Projection {
+    let curve_point = curve.point(parameter);
+    Projection {
+        parameter,
+        distance: vector::distance(&curve_point, point),
+        point: curve_point,
+    }
+}
+
+// Newton can wander off from a poor guess, so the guesses themselves stay in the running.
+pub fn project(curve: &impl Curve, point: &[f64], guesses: &[f64]) -> Projection {
+    guesses
+        .iter()
+        .flat_map(|&guess| [guess, newton(curve, point, guess)])
+        .map(|parameter| projection_at(curve, point, parameter))
+        .min_by(|a, b| a.distance.total_cmp(&b.distance))
+        .unwrap()
+}
+
+pub fn project_bezier(control_points: &[Vec<f64>], point: &[f64]) -> Projection {
+    let guesses = bezier_guesses(control_points, point, (0.0, 1.0));
+    project(&BezierCurve::new(control_points.to_vec()), point, &guesses)
+}
+
+pub fn project_bspline(
+    control_points: &[Vec<f64>],
+    degree: usize,
+    knots: &[f64],
+    point: &[f64],
+) -> Projection {
+    let curve = BSplineCurve::with_knots(control_points.to_vec(), degree, knots.to_vec());
+
+    let mut spans: Vec<f64> = knots[degree - 1..=control_points.len()].to_vec();
+    spans.dedup();
+    let segments = bspline::to_bezier_segments(control_points, degree, knots);
+
+    let guesses: Vec<f64> = segments
+        .iter()
+        .zip(spans.windows(2))
+        .flat_map(|(segment, span)| bezier_guesses(segment, point, (span[0], span[1])))
+        .collect();
+
+    project(&curve, point, &guesses)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A cubic approximation of the quarter of the unit circle from (1, 0) to (0, 1).
+    fn arc() -> Vec<Vec<f64>> {
+        let k = 0.5522847498;
+        vec![
+            vec![1.0, 0.0, 0.0],
+            vec![1.0, k, 0.0],
+            vec![k, 1.0, 0.0],
+            vec![0.0, 1.0, 0.0],
+        ]
+    }
+
+    fn bspline() -> (Vec<Vec<f64>>, usize, Vec<f64>) {
+        let control_points = vec![
+            vec![0.0, 0.0, 0.0],
+            vec![1.0, 2.0, 0.0],
+            vec![2.0, -1.0, 0.0],
+            vec![3.0, 3.0, 1.0],
+            vec![4.0, 0.0, 0.0],
+            vec![5.0, 1.0, 0.0],
+        ];
+        let knots = bspline::generate_knot_vector(control_points.len() - 1, 4);
+        (control_points, 4, knots)
+    }
+
+    #[test]
+    fn points_on_the_curve_project_to_their_parameter() {
+        let curve = BezierCurve::new(arc());
+        for u in [0.0, 0.1, 0.37, 0.5, 0.9, 1.0] {
+            let projection = project_bezier(&arc(), &curve.point(u));
+            assert!((projection.parameter - u).abs() < 1e-9);
+            assert!(projection.distance < 1e-12);
+        }
+
+        let (control_points, degree, knots) = bspline();
+        let curve = BSplineCurve::with_knots(control_points.clone(), degree, knots.clone());
+        for u in [0.2, 0.9, 1.0, 1.5, 2.75] {
+            let projection = project_bspline(&control_points, degree, &knots, &curve.point(u));
+            assert!((projection.parameter - u).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn points_off_the_arc_project_along_the_normal() {
+        let curve = BezierCurve::new(arc());
+        for point in [[1.5, 1.5, 0.0], [0.3, 0.1, 0.0], [2.0, 0.5, 0.0]] {
+            let projection = project_bezier(&arc(), &point);
+            let residual = vector::sub(&point, &projection.point);
+            let tangent = curve.derivative(projection.parameter, 1);
+            assert!(vector::dot(&residual, &tangent).abs() < 1e-9 * vector::norm(&tangent));
+        }
+
+        // By symmetry, the diagonal meets the arc half way.
+        let projection = project_bezier(&arc(), &[1.5, 1.5, 0.0]);
+        assert!((projection.parameter - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn points_beyond_the_ends_project_to_the_ends() {
+        let segment = [vec![0.0, 0.0, 0.0], vec![1.0, 0.0, 0.0]];
+        let projection = project_bezier(&segment, &[2.0, 1.0, 0.0]);
+        assert_eq!(projection.parameter, 1.0);
+        assert!(vector::distance(&projection.point, &[1.0, 0.0, 0.0]) < 1e-12);
+        assert_eq!(project_bezier(&segment, &[-1.0, -1.0, 0.0]).parameter, 0.0);
+
+        let (control_points, degree, knots) = bspline();
+        let projection = project_bspline(&control_points, degree, &knots, &[8.0, 2.0, 0.0]);
+        assert_eq!(projection.parameter, knots[knots.len() - 1]);
+        let projection = project_bspline(&control_points, degree, &knots, &[-3.0, -1.0, 0.0]);
+        assert_eq!(projection.parameter, knots[0]);
+    }
+}
@@ -0,0 +1,576 @@
+use crate::plotter::Format;
+use resvg::{tiny_skia, usvg};
+#[cfg(feature = "serde")]
+use serde::Deserialize;
+use std::fmt;
+use std::fs;
+use std::process::Command;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, OnceLock};
+
+// What a plot shows, independently of the library drawing it.
+#[derive(Clone, Debug)]
+pub struct Figure {
+    pub title: String,
+    pub width: usize,
+    pub height: usize,
+    // Fixed axis ranges; the data decides when missing.
+    pub x_range: Option<(f64, f64)>,
+    pub y_range: Option<(f64, f64)>,
+    pub traces: Vec<Trace>,
+}
+
+// `None` values split a trace into separate pieces, as in the curvature combs.
+#[derive(Clone, Debug)]
+pub struct Trace {
+    pub name: String,
+    pub x_values: Vec<Option<f64>>,
+    pub y_values: Vec<Option<f64>>,
+    pub style: Style,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Style {
+    Line { dashed: bool, width: f64 },
+    Markers { size: f64 },
+}
+
+#[derive(Debug)]
+pub enum RenderError {
+    Io(std::io::Error),
+    Unsupported(String),
+    Raster(String),
+}
+
+impl fmt::Display for RenderError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RenderError::Io(error) => write!(f, "{error}"),
+            RenderError::Unsupported(reason) => write!(f, "{reason}"),
+            RenderError::Raster(reason) => write!(f, "could not rasterise the plot: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for RenderError {}
+
+impl From<std::io::Error> for RenderError {
+    fn from(error: std::io::Error) -> Self {
+        RenderError::Io(error)
+    }
+}
+
+pub trait Renderer {
+    // Writes the figure to `filename` followed by the extension of `format`.
+    fn render(&self, figure: &Figure, filename: &str, format: Format) -> Result<(), RenderError>;
+
+    // Opens the figure for viewing.
+    fn show(&self, figure: &Figure) -> Result<(), RenderError>;
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum Backend {
+    #[default]
+    Native,
+    Plotly,
+}
+
+pub fn renderer(backend: Backend) -> Result<Box<dyn Renderer>, RenderError> {
+    match backend {
+        Backend::Native => Ok(Box::new(NativeRenderer::default())),
+        #[cfg(feature = "plotly")]
+        Backend::Plotly => Ok(Box::new(crate::plotly_renderer::PlotlyRenderer)),
+        #[cfg(not(feature = "plotly"))]
+        Backend::Plotly => Err(RenderError::Unsupported(
+            "the plotly renderer needs the `plotly` feature".to_string(),
+        )),
+    }
+}
+
+// Writes SVG directly and rasterises it for PNG, with no external programs. PDF is left to plotly.
+#[derive(Default)]
+pub struct NativeRenderer {
+    fonts: OnceLock<Arc<usvg::fontdb::Database>>,
+}
+
+impl NativeRenderer {
+    // The system fonts are only looked up for the first PNG, which is the only format needing them.
+    fn fonts(&self) -> Arc<usvg::fontdb::Database> {
+        self.fonts
+            .get_or_init(|| {
+                let mut fonts = usvg::fontdb::Database::new();
+                fonts.load_system_fonts();
+
+                // Without the generic family's default font (Arial) text would be dropped, so any
+                // installed sans-serif family stands in for it.
+                let query = usvg::fontdb::Query {
+                    families: &[usvg::fontdb::Family::SansSerif],
+                    ..Default::default()
+                };
+                if fonts.query(&query).is_none() {
+                    let families: Vec<String> = fonts
+                        .faces()
+                        .map(|face| face.families[0].0.clone())
+                        .collect();
+                    let family = families
+                        .iter()
+                        .find(|family| family.contains("Sans") && !family.contains("Mono"))
+                        .or(families.first());
+                    if let Some(family) = family {
+                        fonts.set_sans_serif_family(family.clone());
+                    }
+                }
+
+                Arc::new(fonts)
+            })
+            .clone()
+    }
+
+    fn png(&self, figure: &Figure, filename: &str) -> Result<(), RenderError> {
+        let options = usvg::Options {
+            fontdb: self.fonts(),
+            ..Default::default()
+        };
+        let tree = usvg::Tree::from_str(&svg(figure), &options)
+            .map_err(|error| RenderError::Raster(error.to_string()))?;
+
+        let mut pixmap = tiny_skia::Pixmap::new(figure.width as u32, figure.height as u32)
+            .ok_or_else(|| RenderError::Raster("the figure has no area".to_string()))?;
+        resvg::render(&tree, tiny_skia::Transform::default(), &mut pixmap.as_mut());
+        pixmap
+            .save_png(filename)
+            .map_err(|error| RenderError::Raster(error.to_string()))
+    }
+}
+
+impl Renderer for NativeRenderer {
+    fn render(&self, figure: &Figure, filename: &str, format: Format) -> Result<(), RenderError> {
+        let file = format!("{}.{}", filename, format.extension());
+        match format {
+            Format::Html => fs::write(file, html(figure))?,
+            Format::Svg => fs::write(file, svg(figure))?,
+            Format::Png => self.png(figure, &file)?,
+            Format::Pdf => {
+                return Err(RenderError::Unsupported(format!(
+                    "{}: the native renderer can't write PDF files, use the plotly renderer",
+                    file
+                )))
+            }
+        }
+        Ok(())
+    }
+
+    // Like plotly, writes a page to the temporary directory and opens it in the browser.
+    fn show(&self, figure: &Figure) -> Result<(), RenderError> {
+        static SHOWN: AtomicUsize = AtomicUsize::new(0);
+        let file = std::env::temp_dir().join(format!(
+            "curves-continuity-{}-{}.html",
+            std::process::id(),
+            SHOWN.fetch_add(1, Ordering::Relaxed)
+        ));
+        fs::write(&file, html(figure))?;
+
+        #[cfg(target_os = "windows")]
+        let mut command = {
+            let mut command = Command::new("cmd");
+            command.args(["/C", "start", ""]);
+            command
+        };
+        #[cfg(target_os = "macos")]
+        let mut command = Command::new("open");
+        #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+        let mut command = Command::new("xdg-open");
+
+        command.arg(&file).spawn()?;
+        Ok(())
+    }
+}
+
+// Plotly's default colours, so both renderers draw the same plots.
+const COLORS: [&str; 10] = [
+    "#1f77b4", "#ff7f0e", "#2ca02c", "#d62728", "#9467bd", "#8c564b", "#e377c2", "#7f7f7f",
+    "#bcbd22", "#17becf",
+];
+
+const MARGIN_LEFT: f64 = 90.0;
+const MARGIN_RIGHT: f64 = 280.0;
+const MARGIN_TOP: f64 = 80.0;
+const MARGIN_BOTTOM: f64 = 60.0;
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+// The range of the finite values, padded by 5% on each side.
+fn data_range(values: impl Iterator<Item = f64>) -> (f64, f64) {
+    let (min, max) = values
+        .filter(|value| value.is_finite())
+        .fold((f64::MAX, f64::MIN), |(min, max), value| {
+            (min.min(value), max.max(value))
+        });
+    if min > max {
+        (0.0, 1.0)
+    } else if min == max {
+        widen((min, max))
+    } else {
+        let margin = 0.05 * (max - min);
+        (min - margin, max + margin)
+    }
+}
+
+// A range of zero width would map every value with a division by zero, so it is widened by one on
+// each side, like the range of a single data value.
+fn widen((start, end): (f64, f64)) -> (f64, f64) {
+    if start == end {
+        (start - 1.0, end + 1.0)
+    } else {
+        (start, end)
+    }
+}
+
+// Round tick values, 1, 2 or 5 times a power of ten apart, with about eight of them on the axis.
+fn ticks((start, end): (f64, f64)) -> (Vec<f64>, usize) {
+    let raw = (end - start) / 8.0;
+    let magnitude = 10f64.powf(raw.log10().floor());
+    let step = magnitude
+        * match raw / magnitude {
+            residual if residual < 1.5 => 1.0,
+            residual if residual < 3.5 => 2.0,
+            residual if residual < 7.5 => 5.0,
+            _ => 10.0,
+        };
+    let decimals = (-step.log10().floor()).max(0.0) as usize;
+
+    let first = (start / step).ceil() as i64;
+    let last = (end / step).floor() as i64;
+    let values = (first..=last).map(|i| i as f64 * step).collect();
+    (values, decimals)
+}
+
+fn tick_label(value: f64, decimals: usize) -> String {
+    let label = format!("{:.*}", decimals, value);
+    // Avoids "-0" for values rounding to zero.
+    if label
+        .trim_start_matches('-')
+        .chars()
+        .all(|c| c == '0' || c == '.')
+    {
+        label.trim_start_matches('-').to_string()
+    } else {
+        label
+    }
+}
+
+// Drops the points of a polyline lying within a tenth of a pixel of the line through their
+// neighbours (Ramer-Douglas-Peucker), since curves are sampled far more finely than they are drawn.
+fn simplify(points: &[(f64, f64)]) -> Vec<(f64, f64)> {
+    const TOLERANCE: f64 = 0.1;
+
+    let mut kept = vec![false; points.len()];
+    kept[0] = true;
+    kept[points.len() - 1] = true;
+    let mut spans = vec![(0, points.len() - 1)];
+    while let Some((first, last)) = spans.pop() {
+        let (x0, y0) = points[first];
+        let (dx, dy) = (points[last].0 - x0, points[last].1 - y0);
+        let length = dx.hypot(dy);
+        let farthest = (first + 1..last)
+            .map(|i| {
+                let (x, y) = points[i];
+                let distance = if length == 0.0 {
+                    (x - x0).hypot(y - y0)
+                } else {
+                    (dx * (y - y0) - dy * (x - x0)).abs() / length
+                };
+                (i, distance)
+            })
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+
+        if let Some((i, distance)) = farthest {
+            if distance > TOLERANCE {
+                kept[i] = true;
+                spans.push((first, i));
+                spans.push((i, last));
+            }
+        }
+    }
+
+    points
+        .iter()
+        .zip(kept)
+        .filter_map(|(&point, kept)| kept.then_some(point))
+        .collect()
+}
+
+// The figure as a standalone SVG document, laid out like plotly's default template.
+pub fn svg(figure: &Figure) -> String {
+    let width = figure.width as f64;
+    let height = figure.height as f64;
+    let plot_width = width - MARGIN_LEFT - MARGIN_RIGHT;
+    let plot_height = height - MARGIN_TOP - MARGIN_BOTTOM;
+
+    let x_range = figure.x_range.unwrap_or_else(|| {
+        data_range(
+            figure
+                .traces
+                .iter()
+                .flat_map(|trace| trace.x_values.iter().flatten().copied()),
+        )
+    });
+    let y_range = figure.y_range.unwrap_or_else(|| {
+        data_range(
+            figure
+                .traces
+                .iter()
+                .flat_map(|trace| trace.y_values.iter().flatten().copied()),
+        )
+    });
+    let (x_range, y_range) = (widen(x_range), widen(y_range));
+    let to_x = |x: f64| MARGIN_LEFT + (x - x_range.0) / (x_range.1 - x_range.0) * plot_width;
+    let to_y = |y: f64| MARGIN_TOP + (y_range.1 - y) / (y_range.1 - y_range.0) * plot_height;
+
+    let mut document = vec![
+        format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" \
+             viewBox=\"0 0 {width} {height}\" font-family=\"'Open Sans', verdana, arial, sans-serif\">"
+        ),
+        format!("<rect width=\"{width}\" height=\"{height}\" fill=\"white\"/>"),
+        format!(
+            "<text x=\"{MARGIN_LEFT}\" y=\"{}\" font-size=\"20\" fill=\"#2a3f5f\">{}</text>",
+            MARGIN_TOP / 2.0,
+            escape(&figure.title)
+        ),
+        format!(
+            "<clipPath id=\"plot-area\"><rect x=\"{MARGIN_LEFT}\" y=\"{MARGIN_TOP}\" \
+             width=\"{plot_width}\" height=\"{plot_height}\"/></clipPath>"
+        ),
+        format!(
+            "<rect x=\"{MARGIN_LEFT}\" y=\"{MARGIN_TOP}\" width=\"{plot_width}\" \
+             height=\"{plot_height}\" fill=\"#e5ecf6\"/>"
+        ),
+    ];
+
+    let (x_ticks, x_decimals) = ticks(x_range);
+    for x in x_ticks {
+        let px = to_x(x);
+        document.push(format!(
+            "<line x1=\"{px:.2}\" y1=\"{MARGIN_TOP}\" x2=\"{px:.2}\" y2=\"{}\" stroke=\"white\"/>",
+            MARGIN_TOP + plot_height
+        ));
+        document.push(format!(
+            "<text x=\"{px:.2}\" y=\"{}\" font-size=\"12\" fill=\"#2a3f5f\" \
+             text-anchor=\"middle\">{}</text>",
+            MARGIN_TOP + plot_height + 20.0,
+            tick_label(x, x_decimals)
+        ));
+    }
+    let (y_ticks, y_decimals) = ticks(y_range);
+    for y in y_ticks {
+        let py = to_y(y);
+        document.push(format!(
+            "<line x1=\"{MARGIN_LEFT}\" y1=\"{py:.2}\" x2=\"{}\" y2=\"{py:.2}\" stroke=\"white\"/>",
+            MARGIN_LEFT + plot_width
+        ));
+        document.push(format!(
+            "<text x=\"{}\" y=\"{py:.2}\" dy=\"0.35em\" font-size=\"12\" fill=\"#2a3f5f\" \
+             text-anchor=\"end\">{}</text>",
+            MARGIN_LEFT - 8.0,
+            tick_label(y, y_decimals)
+        ));
+    }
+
+    document.push("<g clip-path=\"url(#plot-area)\" fill=\"none\">".to_string());
+    for (i, trace) in figure.traces.iter().enumerate() {
+        let color = COLORS[i % COLORS.len()];
+        let points = trace.x_values.iter().zip(&trace.y_values).map(|(x, y)| {
+            x.zip(*y)
+                .filter(|(x, y)| x.is_finite() && y.is_finite())
+                .map(|(x, y)| (to_x(x), to_y(y)))
+        });
+
+        match trace.style {
+            Style::Line { dashed, width } => {
+                let mut data = String::new();
+                let mut piece = Vec::new();
+                for point in points.chain([None]) {
+                    match point {
+                        Some(point) => piece.push(point),
+                        None if piece.is_empty() => {}
+                        None => {
+                            for (j, (x, y)) in simplify(&piece).into_iter().enumerate() {
+                                let command = if j == 0 { "M" } else { "L" };
+                                data.push_str(&format!("{command}{x:.2} {y:.2} "));
+                            }
+                            piece.clear();
+                        }
+                    }
+                }
+                let dash = if dashed {
+                    " stroke-dasharray=\"9 9\""
+                } else {
+                    ""
+                };
+                document.push(format!(
+                    "<path d=\"{}\" stroke=\"{color}\" stroke-width=\"{width}\"{dash} \
+                     stroke-linejoin=\"round\"/>",
+                    data.trim_end()
+                ));
+            }
+            Style::Markers { size } => {
+                for (x, y) in points.flatten() {
+                    document.push(format!(
+                        "<circle cx=\"{x:.2}\" cy=\"{y:.2}\" r=\"{}\" fill=\"{color}\"/>",
+                        size / 2.0
+                    ));
+                }
+            }
+        }
+    }
+    document.push("</g>".to_string());
+
+    // The legend, one entry per trace on the right of the plot.
+    let legend_x = MARGIN_LEFT + plot_width + 20.0;
+    for (i, trace) in figure.traces.iter().enumerate() {
+        let color = COLORS[i % COLORS.len()];
+        let y = MARGIN_TOP + 10.0 + 22.0 * i as f64;
+        document.push(match trace.style {
+            Style::Line { dashed, .. } => format!(
+                "<line x1=\"{legend_x}\" y1=\"{y}\" x2=\"{}\" y2=\"{y}\" stroke=\"{color}\" \
+                 stroke-width=\"2\"{}/>",
+                legend_x + 30.0,
+                if dashed {
+                    " stroke-dasharray=\"5 3\""
+                } else {
+                    ""
+                }
+            ),
+            Style::Markers { .. } => format!(
+                "<circle cx=\"{}\" cy=\"{y}\" r=\"5\" fill=\"{color}\"/>",
+                legend_x + 15.0
+            ),
+        });
+        document.push(format!(
+            "<text x=\"{}\" y=\"{y}\" dy=\"0.35em\" font-size=\"13\" fill=\"#2a3f5f\">{}</text>",
+            legend_x + 40.0,
+            escape(&trace.name)
+        ));
+    }
+
+    document.push("</svg>\n".to_string());
+    document.join("\n")
+}
+
+pub fn html(figure: &Figure) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n</head>\n\
+         <body>\n{}</body>\n</html>\n",
+        escape(&figure.title),
+        svg(figure)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn figure(traces: Vec<Trace>) -> Figure {
+        Figure {
+            title: "Test".to_string(),
+            width: 800,
+            height: 600,
+            x_range: None,
+            y_range: None,
+            traces,
+        }
+    }
+
+    fn line(name: &str, values: &[Option<(f64, f64)>]) -> Trace {
+        Trace {
+            name: name.to_string(),
+            x_values: values.iter().map(|value| value.map(|(x, _)| x)).collect(),
+            y_values: values.iter().map(|value| value.map(|(_, y)| y)).collect(),
+            style: Style::Line {
+                dashed: false,
+                width: 2.0,
+            },
+        }
+    }
+
+    #[test]
+    fn ticks_are_round_values() {
+        assert_eq!(ticks((0.0, 10.0)), ((0..=10).map(f64::from).collect(), 0));
+        assert_eq!(
+            ticks((-3.7, 42.0)),
+            ((0..=8).map(|i| 5.0 * f64::from(i)).collect(), 0)
+        );
+
+        let (values, decimals) = ticks((0.0, 1.0));
+        assert_eq!(decimals, 1);
+        assert_eq!(values.len(), 11);
+        for (i, value) in values.iter().enumerate() {
+            assert!((value - 0.1 * i as f64).abs() < 1e-12);
+        }
+        assert_eq!(tick_label(-1e-17, 1), "0.0");
+    }
+
+    #[test]
+    fn simplify_keeps_the_ends_and_the_corners() {
+        let straight: Vec<(f64, f64)> = (0..=10).map(|i| (i as f64, 2.0 * i as f64)).collect();
+        assert_eq!(simplify(&straight), vec![(0.0, 0.0), (10.0, 20.0)]);
+
+        let corner = [(0.0, 0.0), (1.0, 1.0), (2.0, 2.0), (3.0, 1.0), (4.0, 0.0)];
+        assert_eq!(simplify(&corner), vec![(0.0, 0.0), (2.0, 2.0), (4.0, 0.0)]);
+        assert_eq!(simplify(&[(1.0, 1.0)]), vec![(1.0, 1.0)]);
+    }
+
+    #[test]
+    fn gaps_split_a_trace_into_subpaths() {
+        let document = svg(&figure(vec![
+            line("first", &[Some((0.0, 0.0)), Some((1.0, 1.0))]),
+            line(
+                "second",
+                &[
+                    Some((0.0, 1.0)),
+                    Some((1.0, 2.0)),
+                    None,
+                    Some((2.0, 0.0)),
+                    Some((3.0, 1.0)),
+                ],
+            ),
+        ]));
+
+        let paths: Vec<&str> = document
+            .lines()
+            .filter(|line| line.starts_with("<path"))
+            .collect();
+        assert_eq!(paths.len(), 2);
+        assert_eq!(paths[0].matches('M').count(), 1);
+        assert_eq!(paths[1].matches('M').count(), 2);
+    }
+
+    #[test]
+    fn degenerate_ranges_are_widened() {
+        let mut single = figure(vec![Trace {
+            name: "point".to_string(),
+            x_values: vec![Some(1.0)],
+            y_values: vec![Some(2.0)],
+            style: Style::Markers { size: 8.0 },
+        }]);
+        single.x_range = Some((1.0, 1.0));
+        single.y_range = Some((2.0, 2.0));
+        let document = svg(&single);
+        assert!(!document.contains("NaN") && !document.contains("inf"));
+        assert_eq!(document.matches("<circle").count(), 2);
+
+        let horizontal = figure(vec![line("flat", &[Some((0.0, 3.0)), Some((5.0, 3.0))])]);
+        assert!(!svg(&horizontal).contains("NaN"));
+    }
+}
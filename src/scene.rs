@@ -0,0 +1,606 @@
+use crate::curve::{BSplineCurve, BezierCurve, ClosedBSplineCurve, Curve, Shape};
+use crate::fairing::{self, Energy};
+use crate::io::{self, CurveKind, ParseError};
+use crate::plotter::{Format, Plotter};
+use crate::render::{self, Backend};
+use crate::{arc_length, bounds, bspline, inflection, intersection, join};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+// A whole run: the curves to read or build, the plots to draw from them and the analyses to print.
+// Curves are built in order, so each one can refer to those declared before it.
+#[derive(Clone, Debug, Deserialize)]
+pub struct Scene {
+    #[serde(default = "default_samples")]
+    pub samples: usize,
+    #[serde(default = "default_spikes")]
+    pub spikes: usize,
+    #[serde(default = "default_comb_scale")]
+    pub comb_scale: f64,
+    #[serde(default = "default_formats")]
+    pub formats: Vec<Format>,
+    #[serde(default)]
+    pub renderer: Backend,
+    pub curves: Vec<CurveDefinition>,
+    #[serde(default)]
+    pub plots: Vec<PlotDefinition>,
+    #[serde(default)]
+    pub analyses: Vec<Analysis>,
+}
+
+fn default_samples() -> usize {
+    100000
+}
+
+fn default_spikes() -> usize {
+    200
+}
+
+fn default_comb_scale() -> f64 {
+    0.1
+}
+
+fn default_formats() -> Vec<Format> {
+    vec![Format::Html, Format::Svg, Format::Png]
+}
+
+// The step back from the end of the B-Spline domain where the join derivatives are evaluated.
+fn default_h() -> f64 {
+    2.2250738585072014e-10
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct CurveDefinition {
+    pub name: String,
+    #[serde(flatten)]
+    pub source: Source,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Source {
+    // Files without a header need `type` to say what they describe.
+    File {
+        file: String,
+        #[serde(default, rename = "type")]
+        curve_type: Option<CurveKind>,
+    },
+    // Moves the Bézier curve `second` so it meets the B-Spline `first` with the given continuity, at
+    // the end of `first` or, with `at`, at that parameter of it.
+    Join {
+        first: String,
+        second: String,
+        continuity: usize,
+        #[serde(default)]
+        report: bool,
+        #[serde(default = "default_h")]
+        h: f64,
+        #[serde(default)]
+        at: Option<f64>,
+    },
+    Closed {
+        curve: String,
+    },
+    // The part of the B-Spline `curve` between `start` and `end`, its whole domain by default.
+    Trimmed {
+        curve: String,
+        #[serde(default)]
+        start: Option<f64>,
+        #[serde(default)]
+        end: Option<f64>,
+    },
+    // Keeps the control points a join of the given continuity depends on.
+    Faired {
+        curve: String,
+        #[serde(default)]
+        continuity: usize,
+        energy: Energy,
+        strength: f64,
+    },
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct PlotDefinition {
+    pub title: String,
+    pub output: String,
+    #[serde(default)]
+    pub show: bool,
+    #[serde(default)]
+    pub formats: Option<Vec<Format>>,
+    pub layers: Vec<Layer>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Layer {
+    Curve {
+        curve: String,
+        name: String,
+    },
+    ControlPolygon {
+        curve: String,
+        #[serde(default = "default_polygon_name")]
+        name: String,
+        #[serde(default = "default_markers_name")]
+        markers: String,
+    },
+    Derivative {
+        curve: String,
+        order: usize,
+        name: String,
+    },
+    CurvatureComb {
+        curve: String,
+        name: String,
+    },
+    // Curvature against the parameter, drawn `after` another curve so a join reads left to right.
+    Curvature {
+        curve: String,
+        name: String,
+        #[serde(default)]
+        after: Option<String>,
+    },
+}
+
+fn default_polygon_name() -> String {
+    "Control Polygon".to_string()
+}
+
+fn default_markers_name() -> String {
+    "Control Points".to_string()
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Analysis {
+    Intersections { first: String, second: String },
+    SelfIntersections { curve: String },
+    Inflections { curve: String },
+}
+
+fn pieces(shape: &Shape) -> Option<Vec<intersection::Piece>> {
+    match shape {
+        Shape::Bezier(curve) => Some(intersection::bezier_pieces(&curve.control_points)),
+        Shape::BSpline(curve) => Some(intersection::bspline_pieces(
+            &curve.control_points,
+            curve.degree,
+            &curve.knots,
+        )),
+        Shape::Closed(curve) => {
+            let open = curve.open();
+            Some(intersection::bspline_pieces(
+                &open.control_points,
+                open.degree,
+                &open.knots,
+            ))
+        }
+        Shape::Nurbs(_) | Shape::Path(_) => None,
+    }
+}
+
+// Reading a scene or one of its curve files fails at the line and column of the problem. Once the
+// scene is read serde_json keeps no positions, so a definition that cannot be carried out is
+// located by its place in the scene instead, e.g. `curves[3] "c1"`.
+#[derive(Debug)]
+pub enum SceneError {
+    Parse(ParseError),
+    Definition {
+        file: String,
+        location: String,
+        reason: String,
+    },
+}
+
+impl fmt::Display for SceneError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SceneError::Parse(error) => write!(f, "{error}"),
+            SceneError::Definition {
+                file,
+                location,
+                reason,
+            } => write!(f, "{file}: {location}: {reason}"),
+        }
+    }
+}
+
+impl Error for SceneError {}
+
+impl From<ParseError> for SceneError {
+    fn from(error: ParseError) -> Self {
+        SceneError::Parse(error)
+    }
+}
+
+pub fn read_scene(file: &str) -> Result<Scene, ParseError> {
+    let contents =
+        fs::read_to_string(file).map_err(|error| ParseError::new(file, 0, 0, error.to_string()))?;
+    serde_json::from_str(&contents)
+        .map_err(|error| ParseError::new(file, error.line(), error.column(), error.to_string()))
+}
+
+// The curves of a scene by name, together with their samples once a plot has asked for them.
+struct Curves<'a> {
+    file: &'a str,
+    // The definition being carried out, for its errors.
+    location: String,
+    shapes: HashMap<String, Shape>,
+    parameters: HashMap<String, Vec<f64>>,
+}
+
+impl Curves<'_> {
+    fn error(&self, reason: impl Into<String>) -> SceneError {
+        SceneError::Definition {
+            file: self.file.to_string(),
+            location: self.location.clone(),
+            reason: reason.into(),
+        }
+    }
+
+    fn get(&self, name: &str) -> Result<&Shape, SceneError> {
+        self.shapes
+            .get(name)
+            .ok_or_else(|| self.error(format!("unknown curve {name:?}")))
+    }
+
+    // Evenly spaced in arc length, so the points do not bunch up where the curve slows down.
+    fn parameters(&mut self, name: &str, samples: usize) -> Result<Vec<f64>, SceneError> {
+        if !self.parameters.contains_key(name) {
+            let parameters = arc_length::uniform_parameters(self.get(name)?, samples);
+            self.parameters.insert(name.to_string(), parameters);
+        }
+        Ok(self.parameters[name].clone())
+    }
+}
+
+fn build(curves: &Curves, source: &Source) -> Result<Shape, SceneError> {
+    let error = |reason: String| curves.error(reason);
+    let shape = match source {
+        Source::File { file, curve_type } => {
+            let kind = match curve_type {
+                Some(kind) => *kind,
+                None => io::read_curve(file)?.kind.ok_or_else(|| {
+                    error(format!("the curve type is neither in {file:?} nor given"))
+                })?,
+            };
+            match kind {
+                CurveKind::Bezier => Shape::Bezier(io::read_bezier(file)?),
+                CurveKind::BSpline => Shape::BSpline(io::read_bspline(file)?),
+                CurveKind::Nurbs => Shape::Nurbs(io::read_nurbs(file)?),
+            }
+        }
+        Source::Join {
+            first,
+            second,
+            continuity,
+            report,
+            h,
+            at,
+        } => {
+            let (Shape::BSpline(first_curve), Shape::Bezier(second_curve)) =
+                (curves.get(first)?, curves.get(second)?)
+            else {
+                return Err(error(format!(
+                    "joins need a B-Spline first and a Bézier curve second, {first:?} and {second:?} are not"
+                )));
+            };
+            let (start, end) = first_curve.domain();
+            let (first_points, knots) = match at {
+                Some(u) if *u <= start || end < *u => {
+                    return Err(error(format!(
+                        "cannot join at {u}, outside of the domain of {first:?}"
+                    )))
+                }
+                Some(u) => join::trim(
+                    &first_curve.control_points,
+                    first_curve.degree,
+                    &first_curve.knots,
+                    *u,
+                ),
+                None => (
+                    first_curve.control_points.clone(),
+                    first_curve.knots.clone(),
+                ),
+            };
+            let first_curve =
+                BSplineCurve::with_knots(first_points.clone(), first_curve.degree, knots.clone());
+            let (first_points, second_points) = (&first_points, &second_curve.control_points);
+            let (degree, knots) = (first_curve.degree, &knots);
+            let ((d_s, d_b), control_points) = match continuity {
+                0 => {
+                    let control_points = join::c0_continuity(first_points, second_points);
+                    let d_s = first_curve.point(first_curve.domain().1);
+                    ((d_s, control_points[0].clone()), control_points)
+                }
+                1 => join::c1_continuity(first_points, second_points, degree, knots, *h),
+                2 => join::c2_continuity(first_points, second_points, degree, knots, *h),
+                _ => return Err(error(format!("unsupported continuity C{continuity}"))),
+            };
+            if *report {
+                println!("C{continuity} join of {first} and {second}:");
+                println!("  {first} derivative {continuity}: {d_s:?}");
+                println!("  {second} derivative {continuity}: {d_b:?}");
+            }
+            Shape::Bezier(BezierCurve::new(control_points))
+        }
+        Source::Closed { curve } => {
+            let Shape::BSpline(open) = curves.get(curve)? else {
+                return Err(error(format!(
+                    "only B-Splines can be closed, {curve:?} is not"
+                )));
+            };
+            Shape::Closed(ClosedBSplineCurve::new(
+                open.control_points.clone(),
+                open.degree,
+            ))
+        }
+        Source::Trimmed { curve, start, end } => {
+            let Shape::BSpline(whole) = curves.get(curve)? else {
+                return Err(error(format!(
+                    "only B-Splines can be trimmed, {curve:?} is not"
+                )));
+            };
+            let (domain_start, domain_end) = whole.domain();
+            let (start, end) = (start.unwrap_or(domain_start), end.unwrap_or(domain_end));
+            if start < domain_start || domain_end < end || end <= start {
+                return Err(error(format!(
+                    "cannot trim {curve:?} to {start}..{end}, outside of its domain {domain_start}..{domain_end}"
+                )));
+            }
+            let (control_points, knots) = bspline::extract(
+                &whole.control_points,
+                whole.degree,
+                &whole.knots,
+                start,
+                end,
+            );
+            Shape::BSpline(BSplineCurve::with_knots(
+                control_points,
+                whole.degree,
+                knots,
+            ))
+        }
+        Source::Faired {
+            curve,
+            continuity,
+            energy,
+            strength,
+        } => {
+            if !fairing::is_valid_strength(*strength) {
+                return Err(error(format!(
+                    "the strength must be a finite number, at least 0, not {strength}"
+                )));
+            }
+            let shape = curves.get(curve)?;
+            let fixed = fairing::join_constraints(shape.control_points().len(), *continuity);
+            match shape {
+                Shape::Bezier(bezier) => Shape::Bezier(BezierCurve::new(fairing::fair_bezier(
+                    &bezier.control_points,
+                    &fixed,
+                    *energy,
+                    *strength,
+                ))),
+                Shape::BSpline(bspline) => Shape::BSpline(BSplineCurve::with_knots(
+                    fairing::fair_bspline(
+                        &bspline.control_points,
+                        bspline.degree,
+                        &bspline.knots,
+                        &fixed,
+                        *energy,
+                        *strength,
+                    ),
+                    bspline.degree,
+                    bspline.knots.clone(),
+                )),
+                Shape::Closed(_) | Shape::Nurbs(_) | Shape::Path(_) => {
+                    return Err(error(format!(
+                        "only Bézier curves and B-Splines can be faired, not {curve:?}"
+                    )))
+                }
+            }
+        }
+    };
+
+    Ok(shape)
+}
+
+fn xy(points: &[Vec<f64>]) -> (Vec<f64>, Vec<f64>) {
+    points.iter().map(|point| (point[0], point[1])).unzip()
+}
+
+fn draw(
+    scene: &Scene,
+    curves: &mut Curves,
+    plotter: &mut Plotter,
+    layer: &Layer,
+) -> Result<(), SceneError> {
+    match layer {
+        Layer::Curve { curve, name } => {
+            let parameters = curves.parameters(curve, scene.samples)?;
+            let shape = curves.get(curve)?;
+            let points: Vec<Vec<f64>> = parameters.iter().map(|&u| shape.point(u)).collect();
+            let (x_values, y_values) = xy(&points);
+            plotter.line(x_values, y_values, name, false);
+            plotter.fit(&bounds::shape_box(shape));
+        }
+        Layer::ControlPolygon {
+            curve,
+            name,
+            markers,
+        } => {
+            let shape = curves.get(curve)?;
+            let control_points = shape.control_points();
+            let (x_values, y_values) = xy(&control_points);
+            plotter.markers(x_values, y_values, markers);
+            plotter.fit(&bounds::control_box(&control_points));
+
+            // The polygon of a closed curve runs back to its first control point.
+            let mut polygon = control_points;
+            if let Shape::Closed(_) = shape {
+                polygon.push(polygon[0].clone());
+            }
+            let (x_values, y_values) = xy(&polygon);
+            plotter.line(x_values, y_values, name, true);
+        }
+        Layer::Derivative { curve, order, name } => {
+            let parameters = curves.parameters(curve, scene.samples)?;
+            let shape = curves.get(curve)?;
+            let points: Vec<Vec<f64>> = parameters
+                .iter()
+                .map(|&u| shape.derivative(u, *order))
+                .collect();
+            let (x_values, y_values) = xy(&points);
+            plotter.line(x_values, y_values, name, false);
+            plotter.fit(&bounds::control_box(&points));
+        }
+        Layer::CurvatureComb { curve, name } => {
+            plotter.curvature_comb(curves.get(curve)?, scene.spikes, scene.comb_scale, name);
+        }
+        Layer::Curvature { curve, name, after } => {
+            let offset = match after {
+                Some(previous) => {
+                    let (start, end) = curves.get(previous)?.domain();
+                    end - start
+                }
+                None => 0.0,
+            };
+            plotter.curvature(curves.get(curve)?, scene.spikes, offset, name);
+        }
+    }
+
+    Ok(())
+}
+
+fn analyse(curves: &Curves, analysis: &Analysis) -> Result<(), SceneError> {
+    let pieces = |name: &str| {
+        pieces(curves.get(name)?).ok_or_else(|| {
+            curves.error(format!(
+                "intersections are only supported for Bézier curves and B-Splines, not {name:?}"
+            ))
+        })
+    };
+
+    match analysis {
+        Analysis::Intersections { first, second } => {
+            // Joined curves always meet at the joint, where an end of one is an end of the other,
+            // so only the other crossings are reported.
+            let is_end = |shape: &Shape, u: f64| {
+                let (start, end) = shape.domain();
+                (u - start).abs().min((u - end).abs()) <= 1e-6 * (end - start)
+            };
+            let (first_shape, second_shape) = (curves.get(first)?, curves.get(second)?);
+            let parameters: Vec<(f64, f64)> = intersection::curve_intersections(
+                first_shape,
+                &pieces(first)?,
+                second_shape,
+                &pieces(second)?,
+            )
+            .iter()
+            .map(|crossing| (crossing.first_parameter, crossing.second_parameter))
+            .filter(|&(s, t)| !(is_end(first_shape, s) && is_end(second_shape, t)))
+            .collect();
+            println!("Intersections of {first} and {second}: {parameters:?}");
+        }
+        Analysis::SelfIntersections { curve } => {
+            // A closed curve always meets itself at the seam, where its domain starts and ends.
+            let shape = curves.get(curve)?;
+            let (start, end) = shape.domain();
+            let is_seam = |s: f64, t: f64| {
+                matches!(shape, Shape::Closed(_))
+                    && (s - start).abs().max((t - end).abs()) <= 1e-6 * (end - start)
+            };
+            let parameters: Vec<(f64, f64)> =
+                intersection::self_intersections(shape, &pieces(curve)?)
+                    .iter()
+                    .map(|crossing| (crossing.first_parameter, crossing.second_parameter))
+                    .filter(|&(s, t)| !is_seam(s, t))
+                    .collect();
+            println!("Self-intersections of {curve}: {parameters:?}");
+        }
+        Analysis::Inflections { curve } => {
+            println!(
+                "Inflections of {curve}: {:?}",
+                inflection::inflections(curves.get(curve)?)
+            );
+        }
+    }
+
+    Ok(())
+}
+
+// `file` is the path of the scene, for the errors found while running it.
+pub fn run(scene: &Scene, file: &str) -> Result<(), Box<dyn Error>> {
+    let mut curves = Curves {
+        file,
+        location: String::new(),
+        shapes: HashMap::new(),
+        parameters: HashMap::new(),
+    };
+    for (i, definition) in scene.curves.iter().enumerate() {
+        curves.location = format!("curves[{i}] {:?}", definition.name);
+        let shape = build(&curves, &definition.source)?;
+        curves.shapes.insert(definition.name.clone(), shape);
+    }
+
+    for (i, definition) in scene.plots.iter().enumerate() {
+        curves.location = format!("plots[{i}] {:?}", definition.title);
+        let mut plotter = Plotter::with_renderer(render::renderer(scene.renderer)?);
+        for layer in &definition.layers {
+            draw(scene, &mut curves, &mut plotter, layer)?;
+        }
+
+        if let Some(directory) = Path::new(&definition.output).parent() {
+            fs::create_dir_all(directory)?;
+        }
+        let formats = definition.formats.as_ref().unwrap_or(&scene.formats);
+        plotter.plot_formats(
+            &definition.title,
+            &definition.output,
+            definition.show,
+            formats,
+        )?;
+    }
+
+    for (i, analysis) in scene.analyses.iter().enumerate() {
+        curves.location = format!("analyses[{i}]");
+        analyse(&curves, analysis)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn errors_name_the_definition() {
+        let scene: Scene = serde_json::from_str(
+            r#"{"curves": [{"name": "closed", "kind": "closed", "curve": "missing"}]}"#,
+        )
+        .unwrap();
+        let error = run(&scene, "scene.json").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            r#"scene.json: curves[0] "closed": unknown curve "missing""#
+        );
+    }
+
+    #[test]
+    fn negative_fairing_strength_is_an_error() {
+        let scene: Scene = serde_json::from_str(
+            r#"{"curves": [{"name": "faired", "kind": "faired", "curve": "missing",
+                "continuity": 1, "energy": "bending", "strength": -1}]}"#,
+        )
+        .unwrap();
+        let error = run(&scene, "scene.json").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            r#"scene.json: curves[0] "faired": the strength must be a finite number, at least 0, not -1"#
+        );
+    }
+}
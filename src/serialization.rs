@@ -0,0 +1,250 @@
+use crate::curve::Shape;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+// Bumped whenever the layout of a serialized curve changes, so older files are rejected instead of
+// being misread.
+pub const SCHEMA_VERSION: u32 = 1;
+
+// A serialized curve is the fields of its type next to the schema version, e.g.
+//
+//     {"version": 1, "type": "bspline", "control_points": [[0, 0, 0], ...], "degree": 4,
+//      "knots": [0, 0, 0, 0, 1, ...]}
+//
+// NURBS curves add `weights`, and paths hold their curves in `segments`.
+#[derive(Serialize, Deserialize)]
+struct Document {
+    version: u32,
+    #[serde(flatten)]
+    shape: Shape,
+}
+
+#[derive(Debug)]
+pub enum SerializationError {
+    Json(serde_json::Error),
+    Version(u32),
+    Invalid(String),
+}
+
+impl fmt::Display for SerializationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SerializationError::Json(error) => write!(f, "{error}"),
+            SerializationError::Version(version) => write!(
+                f,
+                "unsupported schema version {version}, expected {SCHEMA_VERSION}"
+            ),
+            SerializationError::Invalid(reason) => write!(f, "{reason}"),
+        }
+    }
+}
+
+impl std::error::Error for SerializationError {}
+
+impl From<serde_json::Error> for SerializationError {
+    fn from(error: serde_json::Error) -> Self {
+        SerializationError::Json(error)
+    }
+}
+
+fn check_knots(count: usize, degree: usize, knots: &[f64]) -> Result<(), String> {
+    if degree == 0 || degree > count {
+        return Err(format!(
+            "{count} control points cannot define a curve of order {degree}"
+        ));
+    }
+    if knots.len() != count + degree {
+        return Err(format!(
+            "{} control points of order {} need {} knots, found {}",
+            count,
+            degree,
+            count + degree,
+            knots.len()
+        ));
+    }
+    if knots.windows(2).any(|pair| pair[1] < pair[0]) {
+        return Err("the knots must be non-decreasing".to_string());
+    }
+    Ok(())
+}
+
+// The invariants the constructors assert, checked here so a bad file is an error, not a panic.
+fn validate(shape: &Shape) -> Result<(), String> {
+    match shape {
+        Shape::Bezier(curve) => {
+            if curve.control_points.is_empty() {
+                return Err("a Bézier curve needs control points".to_string());
+            }
+        }
+        Shape::BSpline(curve) => {
+            check_knots(curve.control_points.len(), curve.degree, &curve.knots)?;
+        }
+        Shape::Closed(curve) => {
+            let count = curve.control_points.len();
+            if curve.degree == 0 || curve.degree > count {
+                return Err(format!(
+                    "{count} control points cannot close a curve of order {}",
+                    curve.degree
+                ));
+            }
+        }
+        Shape::Nurbs(curve) => {
+            check_knots(curve.control_points.len(), curve.degree, &curve.knots)?;
+            if curve.weights.len() != curve.control_points.len() {
+                return Err(format!(
+                    "{} control points need {} weights, found {}",
+                    curve.control_points.len(),
+                    curve.control_points.len(),
+                    curve.weights.len()
+                ));
+            }
+            if curve.weights.iter().any(|&weight| weight <= 0.0) {
+                return Err("the weights must be positive".to_string());
+            }
+        }
+        Shape::Path(path) => {
+            if path.segments.is_empty() {
+                return Err("a path needs segments".to_string());
+            }
+            for segment in &path.segments {
+                validate(segment)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+pub fn to_json(shape: &Shape) -> Result<String, SerializationError> {
+    let document = Document {
+        version: SCHEMA_VERSION,
+        shape: shape.clone(),
+    };
+    Ok(serde_json::to_string_pretty(&document)?)
+}
+
+pub fn from_json(text: &str) -> Result<Shape, SerializationError> {
+    // The version is read first, so a newer layout is reported as such rather than as bad fields.
+    #[derive(Deserialize)]
+    struct Version {
+        version: u32,
+    }
+    let Version { version } = serde_json::from_str(text)?;
+    if version != SCHEMA_VERSION {
+        return Err(SerializationError::Version(version));
+    }
+
+    let document: Document = serde_json::from_str(text)?;
+    validate(&document.shape).map_err(SerializationError::Invalid)?;
+    Ok(document.shape)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curve::{BSplineCurve, BezierCurve, ClosedBSplineCurve, NurbsCurve, Path};
+
+    fn bezier() -> BezierCurve {
+        BezierCurve::new(vec![
+            vec![0.0, 0.0, 0.0],
+            vec![1.0, 2.0, 0.0],
+            vec![3.0, -1.0, 0.5],
+            vec![4.0, 0.0, 0.0],
+        ])
+    }
+
+    fn bspline() -> BSplineCurve {
+        BSplineCurve::with_knots(
+            vec![
+                vec![0.0, 0.0, 0.0],
+                vec![0.5, 1.5, 0.0],
+                vec![1.0, -1.5, 0.0],
+                vec![1.5, 1.0, 0.0],
+                vec![2.0, -1.25, 0.0],
+            ],
+            3,
+            vec![0.0, 0.0, 0.0, 0.25, 0.75, 1.0, 1.0, 1.0],
+        )
+    }
+
+    fn nurbs() -> NurbsCurve {
+        NurbsCurve::with_knots(
+            vec![
+                vec![1.0, 0.0, 0.0],
+                vec![1.0, 1.0, 0.0],
+                vec![0.0, 1.0, 0.0],
+            ],
+            vec![1.0, std::f64::consts::FRAC_1_SQRT_2, 1.0],
+            3,
+            vec![0.0, 0.0, 0.0, 1.0, 1.0, 1.0],
+        )
+    }
+
+    fn round_trip(shape: Shape) {
+        let json = to_json(&shape).unwrap();
+        assert_eq!(from_json(&json).unwrap(), shape);
+    }
+
+    #[test]
+    fn bezier_round_trip() {
+        round_trip(Shape::Bezier(bezier()));
+    }
+
+    #[test]
+    fn bspline_round_trip() {
+        round_trip(Shape::BSpline(bspline()));
+    }
+
+    #[test]
+    fn closed_round_trip() {
+        round_trip(Shape::Closed(ClosedBSplineCurve::new(
+            bspline().control_points,
+            3,
+        )));
+    }
+
+    #[test]
+    fn nurbs_round_trip() {
+        round_trip(Shape::Nurbs(nurbs()));
+    }
+
+    #[test]
+    fn path_round_trip() {
+        round_trip(Shape::Path(Path::new(vec![
+            Shape::BSpline(bspline()),
+            Shape::Bezier(bezier()),
+            Shape::Nurbs(nurbs()),
+        ])));
+    }
+
+    #[test]
+    fn schema_layout() {
+        let json = to_json(&Shape::Nurbs(nurbs())).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["version"], SCHEMA_VERSION);
+        assert_eq!(value["type"], "nurbs");
+        assert_eq!(value["degree"], 3);
+        assert_eq!(value["weights"].as_array().unwrap().len(), 3);
+        assert_eq!(value["knots"].as_array().unwrap().len(), 6);
+    }
+
+    #[test]
+    fn rejects_other_versions() {
+        let json = to_json(&Shape::Bezier(bezier()))
+            .unwrap()
+            .replace("\"version\": 1", "\"version\": 2");
+        assert!(matches!(
+            from_json(&json),
+            Err(SerializationError::Version(2))
+        ));
+    }
+
+    #[test]
+    fn rejects_inconsistent_knots() {
+        let json = r#"{"version": 1, "type": "bspline", "degree": 3,
+            "control_points": [[0, 0, 0], [1, 1, 0], [2, 0, 0]], "knots": [0, 0, 1, 1]}"#;
+        assert!(matches!(
+            from_json(json),
+            Err(SerializationError::Invalid(_))
+        ));
+    }
+}
@@ -0,0 +1,348 @@
+use crate::{bezier, bspline, nurbs, vector};
+
+// Control nets are indexed as `control_points[i][j]`, where `i` runs along `u` and `j` along `v`.
+pub trait Surface {
+    fn domain(&self) -> ((f64, f64), (f64, f64));
+
+    fn point(&self, u: f64, v: f64) -> Vec<f64>;
+
+    // Mixed partial derivative of order `ku` in `u` and `kv` in `v`.
+    fn derivative(&self, u: f64, v: f64, ku: usize, kv: usize) -> Vec<f64>;
+
+    // The normal is undefined where the partial derivatives are parallel, e.g. on a collapsed edge.
+    fn normal(&self, u: f64, v: f64) -> Option<Vec<f64>> {
+        let su = self.derivative(u, v, 1, 0);
+        let sv = self.derivative(u, v, 0, 1);
+        let su_cross_sv = vector::cross(&su, &sv);
+        if vector::norm(&su_cross_sv) <= f64::EPSILON * vector::norm(&su) * vector::norm(&sv) {
+            return None;
+        }
+
+        Some(vector::normalize(&su_cross_sv))
+    }
+}
+
+// Swaps the roles of `u` and `v`, so the columns of a net can be handled as curves.
+pub fn transpose(control_points: &[Vec<Vec<f64>>]) -> Vec<Vec<Vec<f64>>> {
+    (0..control_points[0].len())
+        .map(|j| control_points.iter().map(|row| row[j].clone()).collect())
+        .collect()
+}
+
+#[derive(Clone, Debug)]
+pub struct BezierSurface {
+    pub control_points: Vec<Vec<Vec<f64>>>,
+}
+
+impl BezierSurface {
+    pub fn new(control_points: Vec<Vec<Vec<f64>>>) -> Self {
+        assert!(control_points
+            .iter()
+            .all(|row| row.len() == control_points[0].len()));
+        Self { control_points }
+    }
+}
+
+impl Surface for BezierSurface {
+    fn domain(&self) -> ((f64, f64), (f64, f64)) {
+        ((0.0, 1.0), (0.0, 1.0))
+    }
+
+    fn point(&self, u: f64, v: f64) -> Vec<f64> {
+        self.derivative(u, v, 0, 0)
+    }
+
+    // Every row is a Bézier curve in `v`; their values form the control polygon of a curve in `u`.
+    fn derivative(&self, u: f64, v: f64, ku: usize, kv: usize) -> Vec<f64> {
+        let column: Vec<Vec<f64>> = self
+            .control_points
+            .iter()
+            .map(|row| bezier::derivative_bezier(row, v, kv))
+            .collect();
+
+        bezier::derivative_bezier(&column, u, ku)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct BSplineSurface {
+    pub control_points: Vec<Vec<Vec<f64>>>,
+    pub u_degree: usize,
+    pub v_degree: usize,
+    pub u_knots: Vec<f64>,
+    pub v_knots: Vec<f64>,
+}
+
+impl BSplineSurface {
+    pub fn new(control_points: Vec<Vec<Vec<f64>>>, u_degree: usize, v_degree: usize) -> Self {
+        let u_knots = bspline::generate_knot_vector(control_points.len() - 1, u_degree);
+        let v_knots = bspline::generate_knot_vector(control_points[0].len() - 1, v_degree);
+        Self::with_knots(control_points, u_degree, v_degree, u_knots, v_knots)
+    }
+
+    pub fn with_knots(
+        control_points: Vec<Vec<Vec<f64>>>,
+        u_degree: usize,
+        v_degree: usize,
+        u_knots: Vec<f64>,
+        v_knots: Vec<f64>,
+    ) -> Self {
+        assert!(control_points
+            .iter()
+            .all(|row| row.len() == control_points[0].len()));
+        assert_eq!(u_knots.len(), control_points.len() + u_degree);
+        assert_eq!(v_knots.len(), control_points[0].len() + v_degree);
+        Self {
+            control_points,
+            u_degree,
+            v_degree,
+            u_knots,
+            v_knots,
+        }
+    }
+
+    // Same as `BSplineCurve`: the basis functions vanish at the end of the domain, so both
+    // parameters are evaluated just before it.
+    fn clamp_parameters(&self, u: f64, v: f64) -> (f64, f64) {
+        let ((u_start, u_end), (v_start, v_end)) = self.domain();
+        (
+            u.clamp(u_start, u_end - 1e-10),
+            v.clamp(v_start, v_end - 1e-10),
+        )
+    }
+}
+
+impl Surface for BSplineSurface {
+    fn domain(&self) -> ((f64, f64), (f64, f64)) {
+        let n = self.control_points.len() - 1;
+        let m = self.control_points[0].len() - 1;
+        (
+            (self.u_knots[self.u_degree - 1], self.u_knots[n + 1]),
+            (self.v_knots[self.v_degree - 1], self.v_knots[m + 1]),
+        )
+    }
+
+    fn point(&self, u: f64, v: f64) -> Vec<f64> {
+        self.derivative(u, v, 0, 0)
+    }
+
+    fn derivative(&self, u: f64, v: f64, ku: usize, kv: usize) -> Vec<f64> {
+        let (u, v) = self.clamp_parameters(u, v);
+        let column: Vec<Vec<f64>> = self
+            .control_points
+            .iter()
+            .map(|row| bspline::derivative_bspline(row, v, kv, self.v_degree, &self.v_knots))
+            .collect();
+
+        bspline::derivative_bspline(&column, u, ku, self.u_degree, &self.u_knots)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct NurbsSurface {
+    pub control_points: Vec<Vec<Vec<f64>>>,
+    pub weights: Vec<Vec<f64>>,
+    pub u_degree: usize,
+    pub v_degree: usize,
+    pub u_knots: Vec<f64>,
+    pub v_knots: Vec<f64>,
+}
+
+impl NurbsSurface {
+    pub fn with_knots(
+        control_points: Vec<Vec<Vec<f64>>>,
+        weights: Vec<Vec<f64>>,
+        u_degree: usize,
+        v_degree: usize,
+        u_knots: Vec<f64>,
+        v_knots: Vec<f64>,
+    ) -> Self {
+        assert_eq!(weights.len(), control_points.len());
+        assert!(weights
+            .iter()
+            .zip(&control_points)
+            .all(|(weights, row)| weights.len() == row.len()));
+        // The homogeneous net checks the knots.
+        BSplineSurface::with_knots(
+            control_points.clone(),
+            u_degree,
+            v_degree,
+            u_knots.clone(),
+            v_knots.clone(),
+        );
+        Self {
+            control_points,
+            weights,
+            u_degree,
+            v_degree,
+            u_knots,
+            v_knots,
+        }
+    }
+
+    // The non-rational surface in homogeneous coordinates, (w x, w y, w z, w) at every point.
+    fn homogeneous(&self) -> BSplineSurface {
+        BSplineSurface::with_knots(
+            self.control_points
+                .iter()
+                .zip(&self.weights)
+                .map(|(row, weights)| nurbs::homogeneous(row, weights))
+                .collect(),
+            self.u_degree,
+            self.v_degree,
+            self.u_knots.clone(),
+            self.v_knots.clone(),
+        )
+    }
+}
+
+impl Surface for NurbsSurface {
+    fn domain(&self) -> ((f64, f64), (f64, f64)) {
+        let n = self.control_points.len() - 1;
+        let m = self.control_points[0].len() - 1;
+        (
+            (self.u_knots[self.u_degree - 1], self.u_knots[n + 1]),
+            (self.v_knots[self.v_degree - 1], self.v_knots[m + 1]),
+        )
+    }
+
+    fn point(&self, u: f64, v: f64) -> Vec<f64> {
+        self.derivative(u, v, 0, 0)
+    }
+
+    // The surface version of the NURBS curve derivative, with S = A / w (Piegl & Tiller eq. 4.20):
+    // S^(k,l) = (A^(k,l) - sum binom(k, i) binom(l, j) w^(i,j) S^(k-i,l-j)) / w over (i, j) != (0, 0).
+    fn derivative(&self, u: f64, v: f64, ku: usize, kv: usize) -> Vec<f64> {
+        let homogeneous = self.homogeneous();
+        let dim = self.control_points[0][0].len();
+        let derivatives: Vec<Vec<Vec<f64>>> = (0..=ku)
+            .map(|k| {
+                (0..=kv)
+                    .map(|l| homogeneous.derivative(u, v, k, l))
+                    .collect()
+            })
+            .collect();
+
+        let mut surface_derivatives = vec![vec![Vec::new(); kv + 1]; ku + 1];
+        for k in 0..=ku {
+            for l in 0..=kv {
+                let mut numerator = derivatives[k][l][..dim].to_vec();
+                for i in 0..=k {
+                    for j in 0..=l {
+                        if i == 0 && j == 0 {
+                            continue;
+                        }
+                        let factor =
+                            nurbs::binomial(k, i) * nurbs::binomial(l, j) * derivatives[i][j][dim];
+                        for (coord, lower) in
+                            numerator.iter_mut().zip(&surface_derivatives[k - i][l - j])
+                        {
+                            *coord -= factor * lower;
+                        }
+                    }
+                }
+                surface_derivatives[k][l] = numerator
+                    .iter()
+                    .map(|coord| coord / derivatives[0][0][dim])
+                    .collect();
+            }
+        }
+
+        surface_derivatives[ku].pop().unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The hyperbolic paraboloid z = (x - 1/2)(y - 1/2) through four corners of the unit square.
+    fn bilinear() -> BezierSurface {
+        BezierSurface::new(vec![
+            vec![vec![0.0, 0.0, 0.25], vec![0.0, 1.0, -0.25]],
+            vec![vec![1.0, 0.0, -0.25], vec![1.0, 1.0, 0.25]],
+        ])
+    }
+
+    fn curved() -> BSplineSurface {
+        let control_points = (0..5)
+            .map(|i| {
+                (0..4)
+                    .map(|j| {
+                        let (x, y) = (i as f64, j as f64);
+                        vec![x + 0.2 * y, y, (x - 2.0) * (y - 1.5) * 0.3 + (x * y).sin()]
+                    })
+                    .collect()
+            })
+            .collect();
+        BSplineSurface::new(control_points, 3, 4)
+    }
+
+    #[test]
+    fn bilinear_patch_interpolates_its_corners() {
+        let patch = bilinear();
+        for (u, v) in [(0.0, 0.0), (0.3, 0.8), (0.5, 0.5), (1.0, 0.25)] {
+            let expected = [u, v, (u - 0.5) * (v - 0.5)];
+            assert!(vector::distance(&patch.point(u, v), &expected) < 1e-12);
+        }
+
+        // The same net as a B-Spline surface of order 2 in both directions.
+        let bspline = BSplineSurface::new(patch.control_points.clone(), 2, 2);
+        assert!(vector::distance(&bspline.point(0.3, 0.8), &patch.point(0.3, 0.8)) < 1e-12);
+    }
+
+    #[test]
+    fn partials_match_finite_differences() {
+        let h = 1e-6;
+        let bezier = bilinear();
+        let bspline = curved();
+        let surfaces: [&dyn Surface; 2] = [&bezier, &bspline];
+        for surface in surfaces {
+            let ((u_start, u_end), (v_start, v_end)) = surface.domain();
+            for (s, t) in [(0.2, 0.3), (0.55, 0.7), (0.8, 0.45)] {
+                let (u, v) = (
+                    u_start + s * (u_end - u_start),
+                    v_start + t * (v_end - v_start),
+                );
+                let du = vector::scale(
+                    &vector::sub(&surface.point(u + h, v), &surface.point(u - h, v)),
+                    0.5 / h,
+                );
+                let dv = vector::scale(
+                    &vector::sub(&surface.point(u, v + h), &surface.point(u, v - h)),
+                    0.5 / h,
+                );
+                let duv = vector::scale(
+                    &vector::sub(
+                        &surface.derivative(u, v + h, 1, 0),
+                        &surface.derivative(u, v - h, 1, 0),
+                    ),
+                    0.5 / h,
+                );
+                assert!(vector::distance(&surface.derivative(u, v, 1, 0), &du) < 1e-6);
+                assert!(vector::distance(&surface.derivative(u, v, 0, 1), &dv) < 1e-6);
+                assert!(vector::distance(&surface.derivative(u, v, 1, 1), &duv) < 1e-6);
+            }
+        }
+    }
+
+    #[test]
+    fn normals_are_unit_and_orthogonal_to_the_partials() {
+        let surface = curved();
+        for (u, v) in [(0.1, 0.2), (1.0, 1.0), (2.5, 0.4)] {
+            let normal = surface.normal(u, v).unwrap();
+            assert!((vector::norm(&normal) - 1.0).abs() < 1e-12);
+            for (ku, kv) in [(1, 0), (0, 1)] {
+                let partial = surface.derivative(u, v, ku, kv);
+                assert!(vector::dot(&normal, &partial).abs() < 1e-12 * vector::norm(&partial));
+            }
+        }
+
+        // A collapsed edge has no normal.
+        let cone = BezierSurface::new(vec![
+            vec![vec![0.0, 0.0, 1.0], vec![0.0, 0.0, 1.0]],
+            vec![vec![1.0, 0.0, 0.0], vec![0.0, 1.0, 0.0]],
+        ]);
+        assert!(cone.normal(0.0, 0.5).is_none());
+    }
+}
@@ -0,0 +1,902 @@
+use crate::bounds;
+use crate::curve::{BSplineCurve, BezierCurve, Curve, NurbsCurve, Path, Shape};
+use crate::io::ParseError;
+use crate::{bezier, bspline, vector};
+use std::f64::consts::FRAC_PI_2;
+use std::fs;
+use std::ops::Range;
+
+// Points are read and written in SVG user units, with the y axis pointing down as in SVG. Imported
+// points get z = 0, like 2D input files, and z is dropped when exporting.
+
+struct Tokens<'a> {
+    data: &'a [u8],
+    index: usize,
+}
+
+impl<'a> Tokens<'a> {
+    fn new(data: &'a str) -> Self {
+        Self {
+            data: data.as_bytes(),
+            index: 0,
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.data.get(self.index).copied()
+    }
+
+    fn skip_separators(&mut self) {
+        while self
+            .peek()
+            .is_some_and(|byte| byte.is_ascii_whitespace() || byte == b',')
+        {
+            self.index += 1;
+        }
+    }
+
+    fn is_done(&mut self) -> bool {
+        self.skip_separators();
+        self.peek().is_none()
+    }
+
+    fn command(&mut self) -> Option<u8> {
+        self.skip_separators();
+        let letter = self.peek().filter(|byte| byte.is_ascii_alphabetic())?;
+        self.index += 1;
+        Some(letter)
+    }
+
+    fn at_number(&mut self) -> bool {
+        self.skip_separators();
+        self.peek()
+            .is_some_and(|byte| byte.is_ascii_digit() || matches!(byte, b'+' | b'-' | b'.'))
+    }
+
+    // Numbers may follow each other without separators, as in `1.5.5` or `1-2`.
+    fn number(&mut self) -> Result<f64, (usize, String)> {
+        self.skip_separators();
+        let start = self.index;
+        let digits = |tokens: &mut Self| {
+            let from = tokens.index;
+            while tokens.peek().is_some_and(|byte| byte.is_ascii_digit()) {
+                tokens.index += 1;
+            }
+            tokens.index > from
+        };
+
+        if matches!(self.peek(), Some(b'+' | b'-')) {
+            self.index += 1;
+        }
+        let mut found = digits(self);
+        if self.peek() == Some(b'.') {
+            self.index += 1;
+            found |= digits(self);
+        }
+        if !found {
+            self.index = start;
+            return Err((start, "expected a number".to_string()));
+        }
+        if matches!(self.peek(), Some(b'e' | b'E')) {
+            let mantissa = self.index;
+            self.index += 1;
+            if matches!(self.peek(), Some(b'+' | b'-')) {
+                self.index += 1;
+            }
+            if !digits(self) {
+                self.index = mantissa;
+            }
+        }
+
+        let text = std::str::from_utf8(&self.data[start..self.index]).unwrap();
+        text.parse()
+            .map_err(|_| (start, format!("invalid number `{}`", text)))
+    }
+
+    fn point(&mut self) -> Result<[f64; 2], (usize, String)> {
+        Ok([self.number()?, self.number()?])
+    }
+
+    // Arc flags are a single digit, so `011` reads as two flags and the start of a number.
+    fn flag(&mut self) -> Result<bool, (usize, String)> {
+        self.skip_separators();
+        let flag = match self.peek() {
+            Some(b'0') => false,
+            Some(b'1') => true,
+            _ => return Err((self.index, "expected a flag, 0 or 1".to_string())),
+        };
+        self.index += 1;
+        Ok(flag)
+    }
+}
+
+fn lift(point: [f64; 2]) -> Vec<f64> {
+    vec![point[0], point[1], 0.0]
+}
+
+fn bezier_segment(points: &[[f64; 2]]) -> Shape {
+    Shape::Bezier(BezierCurve::new(points.iter().copied().map(lift).collect()))
+}
+
+// The elliptical arc as rational quadratic Bézier pieces of at most a quarter turn each, using the
+// centre parametrization of the SVG specification (appendix B.2.4).
+fn arc_segments(
+    from: [f64; 2],
+    radii: [f64; 2],
+    rotation: f64,
+    large_arc: bool,
+    sweep: bool,
+    to: [f64; 2],
+) -> Vec<Shape> {
+    if from == to {
+        return Vec::new();
+    }
+    let (mut rx, mut ry) = (radii[0].abs(), radii[1].abs());
+    if rx == 0.0 || ry == 0.0 {
+        return vec![bezier_segment(&[from, to])];
+    }
+
+    let (sin, cos) = rotation.to_radians().sin_cos();
+    let dx = (from[0] - to[0]) / 2.0;
+    let dy = (from[1] - to[1]) / 2.0;
+    let x = cos * dx + sin * dy;
+    let y = -sin * dx + cos * dy;
+
+    // Radii too small to reach the end point are scaled up just enough.
+    let lambda = (x / rx).powi(2) + (y / ry).powi(2);
+    if lambda > 1.0 {
+        rx *= lambda.sqrt();
+        ry *= lambda.sqrt();
+    }
+
+    let numerator = (rx * ry).powi(2) - (rx * y).powi(2) - (ry * x).powi(2);
+    let denominator = (rx * y).powi(2) + (ry * x).powi(2);
+    let sign = if large_arc == sweep { -1.0 } else { 1.0 };
+    let factor = sign * (numerator / denominator).max(0.0).sqrt();
+    let cx = factor * rx * y / ry;
+    let cy = -factor * ry * x / rx;
+
+    let center = [
+        cos * cx - sin * cy + (from[0] + to[0]) / 2.0,
+        sin * cx + cos * cy + (from[1] + to[1]) / 2.0,
+    ];
+    let ellipse = |u: f64, v: f64| {
+        [
+            center[0] + rx * cos * u - ry * sin * v,
+            center[1] + rx * sin * u + ry * cos * v,
+        ]
+    };
+
+    let start = ((y - cy) / ry).atan2((x - cx) / rx);
+    let end = ((-y - cy) / ry).atan2((-x - cx) / rx);
+    let mut angle = (end - start).rem_euclid(std::f64::consts::TAU);
+    if !sweep && angle > 0.0 {
+        angle -= std::f64::consts::TAU;
+    }
+
+    let pieces = (angle.abs() / FRAC_PI_2 - 1e-9).ceil().max(1.0) as usize;
+    let step = angle / pieces as f64;
+    let weight = (step / 2.0).cos();
+    (0..pieces)
+        .map(|i| {
+            let theta = start + i as f64 * step;
+            let middle = theta + step / 2.0;
+            let first = if i == 0 {
+                from
+            } else {
+                ellipse(theta.cos(), theta.sin())
+            };
+            let last = if i == pieces - 1 {
+                to
+            } else {
+                ellipse((theta + step).cos(), (theta + step).sin())
+            };
+            // The tangents at both ends meet at the middle direction, 1 / cos(step / 2) away.
+            let corner = ellipse(middle.cos() / weight, middle.sin() / weight);
+            Shape::Nurbs(NurbsCurve::with_knots(
+                vec![lift(first), lift(corner), lift(last)],
+                vec![1.0, weight, 1.0],
+                3,
+                vec![0.0, 0.0, 0.0, 1.0, 1.0, 1.0],
+            ))
+        })
+        .collect()
+}
+
+fn reflect(point: [f64; 2], center: [f64; 2]) -> [f64; 2] {
+    [2.0 * center[0] - point[0], 2.0 * center[1] - point[1]]
+}
+
+// Errors are returned as the byte offset into the path data and the reason.
+fn parse_data(data: &str) -> Result<Vec<Path>, (usize, String)> {
+    let mut tokens = Tokens::new(data);
+    let mut paths = Vec::new();
+    let mut segments: Vec<Shape> = Vec::new();
+    let mut current = [0.0, 0.0];
+    let mut start = [0.0, 0.0];
+    // The last control point of the previous cubic or quadratic, reflected by `S` and `T`.
+    let mut cubic_control: Option<[f64; 2]> = None;
+    let mut quadratic_control: Option<[f64; 2]> = None;
+    let mut command: Option<u8> = None;
+
+    while !tokens.is_done() {
+        let position = tokens.index;
+        let letter = match tokens.command() {
+            Some(letter) => letter,
+            // Repeated arguments repeat the command, and the ones after a move are lines.
+            None => match command {
+                Some(b'M') => b'L',
+                Some(b'm') => b'l',
+                Some(b'Z' | b'z') | None => {
+                    return Err((position, "expected a command".to_string()))
+                }
+                Some(letter) => letter,
+            },
+        };
+        if command.is_none() && !matches!(letter, b'M' | b'm') {
+            return Err((position, "path data must start with a move".to_string()));
+        }
+
+        let relative = letter.is_ascii_lowercase();
+        let offset = |point: [f64; 2], current: [f64; 2]| {
+            if relative {
+                [point[0] + current[0], point[1] + current[1]]
+            } else {
+                point
+            }
+        };
+        let mut next_cubic = None;
+        let mut next_quadratic = None;
+
+        match letter.to_ascii_uppercase() {
+            b'M' => {
+                if !segments.is_empty() {
+                    paths.push(Path::new(std::mem::take(&mut segments)));
+                }
+                current = offset(tokens.point()?, current);
+                start = current;
+            }
+            b'L' => {
+                let to = offset(tokens.point()?, current);
+                segments.push(bezier_segment(&[current, to]));
+                current = to;
+            }
+            b'H' => {
+                let x = tokens.number()? + if relative { current[0] } else { 0.0 };
+                let to = [x, current[1]];
+                segments.push(bezier_segment(&[current, to]));
+                current = to;
+            }
+            b'V' => {
+                let y = tokens.number()? + if relative { current[1] } else { 0.0 };
+                let to = [current[0], y];
+                segments.push(bezier_segment(&[current, to]));
+                current = to;
+            }
+            b'C' | b'S' => {
+                let first = if letter.eq_ignore_ascii_case(&b'C') {
+                    offset(tokens.point()?, current)
+                } else {
+                    cubic_control.map_or(current, |control| reflect(control, current))
+                };
+                let second = offset(tokens.point()?, current);
+                let to = offset(tokens.point()?, current);
+                segments.push(bezier_segment(&[current, first, second, to]));
+                next_cubic = Some(second);
+                current = to;
+            }
+            b'Q' | b'T' => {
+                let control = if letter.eq_ignore_ascii_case(&b'Q') {
+                    offset(tokens.point()?, current)
+                } else {
+                    quadratic_control.map_or(current, |control| reflect(control, current))
+                };
+                let to = offset(tokens.point()?, current);
+                segments.push(bezier_segment(&[current, control, to]));
+                next_quadratic = Some(control);
+                current = to;
+            }
+            b'A' => {
+                let radii = tokens.point()?;
+                let rotation = tokens.number()?;
+                let large_arc = tokens.flag()?;
+                let sweep = tokens.flag()?;
+                let to = offset(tokens.point()?, current);
+                segments.extend(arc_segments(current, radii, rotation, large_arc, sweep, to));
+                current = to;
+            }
+            b'Z' => {
+                if current != start {
+                    segments.push(bezier_segment(&[current, start]));
+                }
+                if !segments.is_empty() {
+                    paths.push(Path::new(std::mem::take(&mut segments)));
+                }
+                current = start;
+            }
+            _ => return Err((position, format!("unknown command `{}`", letter as char))),
+        }
+
+        cubic_control = next_cubic;
+        quadratic_control = next_quadratic;
+        command = Some(letter);
+
+        if matches!(letter, b'Z' | b'z') && tokens.at_number() {
+            return Err((tokens.index, "expected a command".to_string()));
+        }
+    }
+
+    if !segments.is_empty() {
+        paths.push(Path::new(segments));
+    }
+    Ok(paths)
+}
+
+fn position(contents: &str, offset: usize) -> (usize, usize) {
+    let before = &contents[..offset];
+    let line = before.matches('\n').count() + 1;
+    let column = before[before.rfind('\n').map_or(0, |newline| newline + 1)..]
+        .chars()
+        .count()
+        + 1;
+    (line, column)
+}
+
+// One path per subpath of the data, that is, per move or close.
+pub fn parse_path(data: &str) -> Result<Vec<Path>, ParseError> {
+    parse_data(data).map_err(|(offset, reason)| {
+        let (line, column) = position(data, offset);
+        ParseError::new("<path data>", line, column, reason)
+    })
+}
+
+// The byte range of the `d` value of an element, if it has one, and the end of its tag.
+struct Tag {
+    data: Option<Range<usize>>,
+    end: usize,
+}
+
+// The tag whose attributes start at `start`. Values are skipped whole, so a `>` or `d=` quoted inside
+// another attribute is not taken for markup.
+fn path_tag(contents: &str, start: usize) -> Result<Tag, (usize, String)> {
+    let bytes = contents.as_bytes();
+    let skip_whitespace = |mut index: usize| {
+        while bytes
+            .get(index)
+            .is_some_and(|byte| byte.is_ascii_whitespace())
+        {
+            index += 1;
+        }
+        index
+    };
+
+    let mut data = None;
+    let mut index = start;
+    loop {
+        index = skip_whitespace(index);
+        match bytes.get(index) {
+            None => return Err((start, "unterminated tag".to_string())),
+            Some(b'>') => {
+                return Ok(Tag {
+                    data,
+                    end: index + 1,
+                })
+            }
+            Some(b'/') => {
+                index += 1;
+                continue;
+            }
+            _ => {}
+        }
+
+        let name_start = index;
+        while bytes
+            .get(index)
+            .is_some_and(|&byte| !byte.is_ascii_whitespace() && !matches!(byte, b'=' | b'>' | b'/'))
+        {
+            index += 1;
+        }
+        let name = &contents[name_start..index];
+        index = skip_whitespace(index);
+        // An attribute without a value.
+        if bytes.get(index) != Some(&b'=') {
+            continue;
+        }
+
+        index = skip_whitespace(index + 1);
+        let quote = match bytes.get(index) {
+            Some(&quote @ (b'"' | b'\'')) => quote as char,
+            _ => return Err((index, "expected a quoted value".to_string())),
+        };
+        let Some(length) = contents[index + 1..].find(quote) else {
+            return Err((index, "unterminated value".to_string()));
+        };
+        if name == "d" {
+            data = Some(index + 1..index + 1 + length);
+        }
+        index += length + 2;
+    }
+}
+
+// The subpaths of every `<path>` element of an SVG file, in document order. Transforms and styles
+// are ignored.
+pub fn read_svg(file: &str) -> Result<Vec<Path>, ParseError> {
+    let contents =
+        fs::read_to_string(file).map_err(|error| ParseError::new(file, 0, 0, error.to_string()))?;
+    let error = |offset: usize, reason: String| {
+        let (line, column) = position(&contents, offset);
+        ParseError::new(file, line, column, reason)
+    };
+
+    let mut paths = Vec::new();
+    let mut rest = 0;
+    while let Some(found) = contents[rest..].find("<path") {
+        let attributes = rest + found + "<path".len();
+        // Other elements starting with the same letters, like `<pathology>`.
+        let is_path = contents[attributes..]
+            .bytes()
+            .next()
+            .is_some_and(|byte| byte.is_ascii_whitespace() || matches!(byte, b'/' | b'>'));
+        if !is_path {
+            rest = attributes;
+            continue;
+        }
+
+        let tag =
+            path_tag(&contents, attributes).map_err(|(offset, reason)| error(offset, reason))?;
+        rest = tag.end;
+        if let Some(data) = tag.data {
+            paths.extend(
+                parse_data(&contents[data.clone()])
+                    .map_err(|(offset, reason)| error(data.start + offset, reason))?,
+            );
+        }
+    }
+
+    Ok(paths)
+}
+
+fn cubic(points: &[Vec<f64>]) -> Vec<Vec<f64>> {
+    let mut points = points.to_vec();
+    while points.len() < 4 {
+        points = bezier::elevate(&points);
+    }
+    points
+}
+
+// Distance from `point` to the closest point of the cubic, refined with Newton's method from the
+// parameter `t`. The curve and the cubic are parametrized differently, so comparing them at the same
+// parameter would overestimate the deviation, badly so for rational arcs.
+fn distance_to_cubic(cubic: &[Vec<f64>], point: &[f64], mut t: f64) -> f64 {
+    for _ in 0..8 {
+        let offset = vector::sub(&bezier::bezier(cubic, t), point);
+        let first = bezier::derivative_bezier(cubic, t, 1);
+        let second = bezier::derivative_bezier(cubic, t, 2);
+        let slope = vector::dot(&first, &first) + vector::dot(&offset, &second);
+        if slope <= 0.0 {
+            break;
+        }
+        t = (t - vector::dot(&offset, &first) / slope).clamp(0.0, 1.0);
+    }
+    vector::distance(&bezier::bezier(cubic, t), point)
+}
+
+// The multiples of the end tangents placing the middle of the cubic on `middle`, in the least
+// squares sense. Using the derivatives themselves, as a Hermite cubic does, carries the speed of the
+// curve's own parametrization over, which for rational arcs is far from the best cubic.
+fn handle_lengths(
+    first: &[f64],
+    first_tangent: &[f64],
+    last: &[f64],
+    last_tangent: &[f64],
+    middle: &[f64],
+) -> Option<(f64, f64)> {
+    // B(1/2) = (P0 + P3) / 2 + 3/8 (a T0 - b T3)
+    let target = vector::scale(
+        &vector::sub(middle, &vector::scale(&vector::add(first, last), 0.5)),
+        8.0 / 3.0,
+    );
+    let t00 = vector::dot(first_tangent, first_tangent);
+    let t01 = vector::dot(first_tangent, last_tangent);
+    let t11 = vector::dot(last_tangent, last_tangent);
+    let determinant = t00 * t11 - t01 * t01;
+    if determinant <= 1e-12 * t00 * t11 {
+        return None;
+    }
+
+    let r0 = vector::dot(first_tangent, &target);
+    let r1 = -vector::dot(last_tangent, &target);
+    let a = (t11 * r0 + t01 * r1) / determinant;
+    let b = (t01 * r0 + t00 * r1) / determinant;
+    (a > 0.0 && b > 0.0).then_some((a, b))
+}
+
+// Cubics through the end points of `start..end` along the end tangents, halved until they stay
+// within `tolerance` of the curve at the sampled points.
+fn approximate(curve: &impl Curve, start: f64, end: f64, tolerance: f64) -> Vec<Vec<Vec<f64>>> {
+    const SAMPLES: usize = 16;
+    const MAX_DEPTH: usize = 24;
+
+    fn fit(
+        curve: &impl Curve,
+        start: f64,
+        end: f64,
+        tolerance: f64,
+        depth: usize,
+        cubics: &mut Vec<Vec<Vec<f64>>>,
+    ) {
+        let h = end - start;
+        let first = curve.point(start);
+        let last = curve.point(end);
+        // The end derivative is taken just inside the span, since a knot there may break it.
+        let first_tangent = curve.derivative(start, 1);
+        let last_tangent = curve.derivative(end - 1e-9 * h, 1);
+        let (a, b) = handle_lengths(
+            &first,
+            &first_tangent,
+            &last,
+            &last_tangent,
+            &curve.point(start + h / 2.0),
+        )
+        .unwrap_or((h / 3.0, h / 3.0));
+        let candidate = vec![
+            first.clone(),
+            vector::add(&first, &vector::scale(&first_tangent, a)),
+            vector::sub(&last, &vector::scale(&last_tangent, b)),
+            last,
+        ];
+
+        let deviation = (1..SAMPLES)
+            .map(|i| {
+                let t = i as f64 / SAMPLES as f64;
+                distance_to_cubic(&candidate, &curve.point(start + t * h), t)
+            })
+            .fold(0.0, f64::max);
+        if deviation <= tolerance || depth == MAX_DEPTH {
+            cubics.push(candidate);
+        } else {
+            let middle = start + h / 2.0;
+            fit(curve, start, middle, tolerance, depth + 1, cubics);
+            fit(curve, middle, end, tolerance, depth + 1, cubics);
+        }
+    }
+
+    let mut cubics = Vec::new();
+    fit(curve, start, end, tolerance, 0, &mut cubics);
+    cubics
+}
+
+// The distinct knots inside the domain, where the pieces of a spline meet.
+fn breakpoints(curve: &impl Curve, knots: &[f64]) -> Vec<f64> {
+    let (start, end) = curve.domain();
+    let mut breakpoints: Vec<f64> = knots
+        .iter()
+        .copied()
+        .filter(|&u| start <= u && u <= end)
+        .collect();
+    breakpoints.dedup();
+    breakpoints
+}
+
+fn approximate_spans(curve: &impl Curve, knots: &[f64], tolerance: f64) -> Vec<Vec<Vec<f64>>> {
+    breakpoints(curve, knots)
+        .windows(2)
+        .flat_map(|span| approximate(curve, span[0], span[1], tolerance))
+        .collect()
+}
+
+fn is_clamped(degree: usize, knots: &[f64]) -> bool {
+    knots[..degree].iter().all(|&u| u == knots[0])
+        && knots[knots.len() - degree..]
+            .iter()
+            .all(|&u| u == knots[knots.len() - 1])
+}
+
+fn bspline_cubics(curve: &BSplineCurve, tolerance: f64) -> Vec<Vec<Vec<f64>>> {
+    if (2..=4).contains(&curve.degree) && is_clamped(curve.degree, &curve.knots) {
+        bspline::to_bezier_segments(&curve.control_points, curve.degree, &curve.knots)
+            .iter()
+            .map(|segment| cubic(segment))
+            .collect()
+    } else {
+        approximate_spans(curve, &curve.knots, tolerance)
+    }
+}
+
+// The shape as cubic Bézier control points, exact for polynomial pieces of degree 3 or less and
+// otherwise within `tolerance`.
+pub fn cubic_segments(shape: &Shape, tolerance: f64) -> Vec<Vec<Vec<f64>>> {
+    match shape {
+        Shape::Bezier(curve) if curve.control_points.len() <= 4 => {
+            vec![cubic(&curve.control_points)]
+        }
+        Shape::Bezier(curve) => approximate(curve, 0.0, 1.0, tolerance),
+        Shape::BSpline(curve) => bspline_cubics(curve, tolerance),
+        Shape::Closed(curve) => bspline_cubics(&curve.open(), tolerance),
+        // Equal weights cancel out, leaving a plain B-Spline.
+        Shape::Nurbs(curve) if curve.weights.iter().all(|&w| w == curve.weights[0]) => {
+            bspline_cubics(
+                &BSplineCurve::with_knots(
+                    curve.control_points.clone(),
+                    curve.degree,
+                    curve.knots.clone(),
+                ),
+                tolerance,
+            )
+        }
+        Shape::Nurbs(curve) => approximate_spans(curve, &curve.knots, tolerance),
+        Shape::Path(path) => path
+            .segments
+            .iter()
+            .flat_map(|segment| cubic_segments(segment, tolerance))
+            .collect(),
+    }
+}
+
+// The `d` attribute of an SVG path drawing the shape. A new subpath starts wherever consecutive
+// cubics don't meet, and subpaths ending where they started are closed.
+pub fn path_data(shape: &Shape, tolerance: f64) -> String {
+    let cubics = cubic_segments(shape, tolerance);
+    let coordinates = |point: &[f64]| format!("{} {}", point[0], point[1]);
+    let meets = |p0: &[f64], p1: &[f64]| vector::distance(&p0[..2], &p1[..2]) <= 1e-9;
+
+    let mut data = Vec::new();
+    let mut start: Option<&Vec<f64>> = None;
+    for (i, cubic) in cubics.iter().enumerate() {
+        if start.is_none() {
+            data.push(format!("M {}", coordinates(&cubic[0])));
+            start = Some(&cubic[0]);
+        }
+        data.push(format!(
+            "C {} {} {}",
+            coordinates(&cubic[1]),
+            coordinates(&cubic[2]),
+            coordinates(&cubic[3])
+        ));
+
+        let ends = cubics
+            .get(i + 1)
+            .is_none_or(|next| !meets(&cubic[3], &next[0]));
+        if ends {
+            if meets(&cubic[3], start.unwrap()) {
+                data.push("Z".to_string());
+            }
+            start = None;
+        }
+    }
+
+    data.join(" ")
+}
+
+// A standalone SVG document drawing every shape, framed around their control points.
+pub fn write_svg(file: &str, shapes: &[Shape], tolerance: f64) -> std::io::Result<()> {
+    let paths: Vec<String> = shapes
+        .iter()
+        .map(|shape| path_data(shape, tolerance))
+        .collect();
+    let points: Vec<Vec<f64>> = shapes
+        .iter()
+        .flat_map(|shape| cubic_segments(shape, tolerance))
+        .flatten()
+        .map(|point| point[..2].to_vec())
+        .collect();
+
+    let (min, size) = if points.is_empty() {
+        (vec![0.0, 0.0], vec![1.0, 1.0])
+    } else {
+        let bounds = bounds::control_box(&points);
+        let margin = (0.05 * bounds.diagonal()).max(1e-3);
+        let min = vec![bounds.min[0] - margin, bounds.min[1] - margin];
+        let size = vec![
+            bounds.max[0] - bounds.min[0] + 2.0 * margin,
+            bounds.max[1] - bounds.min[1] + 2.0 * margin,
+        ];
+        (min, size)
+    };
+
+    let mut document = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"{} {} {} {}\">\n",
+        min[0], min[1], size[0], size[1]
+    );
+    for data in paths {
+        document.push_str(&format!(
+            "  <path d=\"{}\" fill=\"none\" stroke=\"black\" stroke-width=\"1\" \
+             vector-effect=\"non-scaling-stroke\"/>\n",
+            data
+        ));
+    }
+    document.push_str("</svg>\n");
+
+    fs::write(file, document)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(data: &str) -> Vec<Path> {
+        parse_path(data).unwrap()
+    }
+
+    fn same_cubics(a: &Shape, b: &Shape) -> bool {
+        let (a, b) = (cubic_segments(a, 1e-6), cubic_segments(b, 1e-6));
+        a.len() == b.len()
+            && a.iter()
+                .flatten()
+                .zip(b.iter().flatten())
+                .all(|(p, q)| vector::distance(p, q) <= 1e-9)
+    }
+
+    // Points along every segment, including their ends.
+    fn samples(path: &Path) -> Vec<Vec<f64>> {
+        path.segments
+            .iter()
+            .flat_map(|segment| {
+                let (start, end) = segment.domain();
+                (0..=20).map(move |i| segment.point(start + (end - start) * i as f64 / 20.0))
+            })
+            .collect()
+    }
+
+    fn error_position(data: &str) -> (usize, usize) {
+        let error = parse_path(data).unwrap_err();
+        (error.line, error.column)
+    }
+
+    #[test]
+    fn relative_and_shorthand_commands() {
+        assert_eq!(
+            parse("m 1 1 h 2 v 2 H 0 V 1"),
+            parse("M 1 1 L 3 1 L 3 3 L 0 3 L 0 1")
+        );
+        // `S` and `T` reflect the previous control point about the current point.
+        assert_eq!(
+            parse("M 0 0 C 1 1 2 1 3 0 s 2 -1 3 0"),
+            parse("M 0 0 C 1 1 2 1 3 0 C 4 -1 5 -1 6 0")
+        );
+        assert_eq!(
+            parse("M 0 0 Q 1 1 2 0 t 2 0 T 6 0"),
+            parse("M 0 0 Q 1 1 2 0 Q 3 -1 4 0 Q 5 1 6 0")
+        );
+        // Without a previous curve of the same kind, the control point is the current point.
+        assert_eq!(
+            parse("M 0 0 L 1 0 S 2 1 3 0"),
+            parse("M 0 0 L 1 0 C 1 0 2 1 3 0")
+        );
+        assert_eq!(parse("M 0 0 L 1 0 T 3 0"), parse("M 0 0 L 1 0 Q 1 0 3 0"));
+        // Extra coordinates after a move are lines, and numbers need no separators.
+        assert_eq!(parse("M0,0 1-1.5.5.5"), parse("M 0 0 L 1 -1.5 L 0.5 0.5"));
+    }
+
+    #[test]
+    fn close_returns_to_the_start() {
+        let paths = parse("M 0 0 h 1 v 1 z m 2 0 h 1 Z");
+        assert_eq!(paths.len(), 2);
+        assert_eq!(paths, parse("M 0 0 L 1 0 L 1 1 L 0 0 M 2 0 L 3 0 L 2 0"));
+        // Closing an already closed subpath adds no segment.
+        assert_eq!(parse("M 0 0 L 1 0 L 0 0 Z")[0].segments.len(), 2);
+    }
+
+    #[test]
+    fn arcs_lie_on_the_circle() {
+        // Half and three quarters of the unit circle.
+        for (data, pieces) in [("M 1 0 A 1 1 0 0 1 -1 0", 2), ("M 1 0 a 1 1 0 1 0 -1 1", 3)] {
+            let paths = parse(data);
+            assert_eq!(paths[0].segments.len(), pieces);
+            for point in samples(&paths[0]) {
+                assert!((vector::norm(&point) - 1.0).abs() < 1e-12);
+            }
+        }
+
+        // Radii too small to reach the end point are scaled up to a half circle of radius 2.
+        let paths = parse("M 2 0 A 1 1 0 0 0 -2 0");
+        for point in samples(&paths[0]) {
+            assert!((vector::norm(&point) - 2.0).abs() < 1e-12);
+        }
+        assert!(samples(&paths[0]).iter().all(|point| point[1] <= 1e-12));
+    }
+
+    #[test]
+    fn path_data_round_trip() {
+        for data in [
+            "M 0 0 C 1 2 3 2 4 0 S 7 -2 8 0",
+            "M 0 0 Q 1 1 2 0 T 4 0 L 4 -2 Z",
+            "M 1 1 h 2 v 2 h -2 z M 5 5 l 1 0",
+        ] {
+            let shape = Shape::Path(Path::new(
+                parse(data)
+                    .into_iter()
+                    .flat_map(|path| path.segments)
+                    .collect(),
+            ));
+            let exported = path_data(&shape, 1e-6);
+            let imported = Shape::Path(Path::new(
+                parse(&exported)
+                    .into_iter()
+                    .flat_map(|path| path.segments)
+                    .collect(),
+            ));
+            assert!(same_cubics(&shape, &imported), "{}", exported);
+        }
+    }
+
+    #[test]
+    fn exported_arcs_stay_within_the_tolerance() {
+        let tolerance = 1e-4;
+        let circle = Shape::Path(parse("M 1 0 A 1 1 0 1 1 -1 0 A 1 1 0 1 1 1 0").remove(0));
+        let exported = path_data(&circle, tolerance);
+        assert!(exported.ends_with('Z'));
+        for point in samples(&parse(&exported)[0]) {
+            assert!((vector::norm(&point) - 1.0).abs() <= tolerance);
+        }
+    }
+
+    #[test]
+    fn errors_point_at_the_data() {
+        assert_eq!(error_position("L 1 1"), (1, 1));
+        assert_eq!(error_position("M 0 0 L 1"), (1, 10));
+        assert_eq!(error_position("M 0 0\n  X 1 1"), (2, 3));
+        assert_eq!(error_position("M 0 0 A 1 1 0 2 0 1 1"), (1, 15));
+        assert_eq!(error_position("M 0 0 Z 1 1"), (1, 9));
+    }
+
+    fn read(name: &str, contents: &str) -> Result<Vec<Path>, ParseError> {
+        let file = std::env::temp_dir().join(format!("curves-continuity-svg-test-{}.svg", name));
+        fs::write(&file, contents).unwrap();
+        let paths = read_svg(file.to_str().unwrap());
+        fs::remove_file(&file).unwrap();
+        paths
+    }
+
+    #[test]
+    fn reads_every_path_element() {
+        let paths = read(
+            "elements",
+            "<svg xmlns=\"http://www.w3.org/2000/svg\">\n\
+             <pathology d=\"M 9 9 L 8 8\"/>\n\
+             <path id=\"a>b\" data-d=\"M 9 9 L 8 8\" d=\"M 0 0 L 1 0\"/>\n\
+             <g><path\n  d = 'M 0 0 L 0 1' fill=\"none\"></path></g>\n\
+             <path fill=\"none\"/>\n\
+             </svg>\n",
+        )
+        .unwrap();
+        assert_eq!(paths, parse("M 0 0 L 1 0 M 0 0 L 0 1"));
+    }
+
+    #[test]
+    fn write_svg_round_trip() {
+        let shapes = vec![
+            Shape::Path(parse("M 0 0 C 1 2 3 2 4 0 Z").remove(0)),
+            Shape::Bezier(BezierCurve::new(vec![
+                vec![0.0, 0.0, 0.0],
+                vec![1.0, 3.0, 0.0],
+                vec![2.0, 0.0, 0.0],
+            ])),
+        ];
+        let file = std::env::temp_dir().join("curves-continuity-svg-test-write.svg");
+        write_svg(file.to_str().unwrap(), &shapes, 1e-6).unwrap();
+        let paths = read_svg(file.to_str().unwrap());
+        fs::remove_file(&file).unwrap();
+
+        let paths = paths.unwrap();
+        assert_eq!(paths.len(), shapes.len());
+        for (shape, path) in shapes.iter().zip(paths) {
+            assert!(same_cubics(shape, &Shape::Path(path)));
+        }
+    }
+
+    #[test]
+    fn read_errors_point_into_the_file() {
+        let error = read("data", "<svg>\n<path d=\"M 0 0\n L 1 x\"/>\n</svg>\n").unwrap_err();
+        assert_eq!(
+            (error.line, error.column, error.reason.as_str()),
+            (3, 6, "expected a number")
+        );
+
+        let error = read("unquoted", "<svg>\n  <path d=M0,0/>\n</svg>\n").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 11));
+
+        let error = read("unterminated", "<svg>\n<path id=\"a\" d=\"M 0 0/>\n").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 16));
+    }
+}
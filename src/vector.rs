@@ -0,0 +1,35 @@
+pub fn add(p0: &[f64], p1: &[f64]) -> Vec<f64> {
+    p0.iter().zip(p1).map(|(a, b)| a + b).collect()
+}
+
+pub fn sub(p0: &[f64], p1: &[f64]) -> Vec<f64> {
+    p0.iter().zip(p1).map(|(a, b)| a - b).collect()
+}
+
+pub fn scale(p: &[f64], factor: f64) -> Vec<f64> {
+    p.iter().map(|coord| coord * factor).collect()
+}
+
+pub fn dot(p0: &[f64], p1: &[f64]) -> f64 {
+    p0.iter().zip(p1).map(|(a, b)| a * b).sum()
+}
+
+pub fn norm(p: &[f64]) -> f64 {
+    dot(p, p).sqrt()
+}
+
+pub fn distance(p0: &[f64], p1: &[f64]) -> f64 {
+    norm(&sub(p0, p1))
+}
+
+pub fn cross(p0: &[f64], p1: &[f64]) -> Vec<f64> {
+    vec![
+        p0[1] * p1[2] - p0[2] * p1[1],
+        p0[2] * p1[0] - p0[0] * p1[2],
+        p0[0] * p1[1] - p0[1] * p1[0],
+    ]
+}
+
+pub fn normalize(p: &[f64]) -> Vec<f64> {
+    scale(p, 1.0 / norm(p))
+}